@@ -0,0 +1,39 @@
+CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
+
+CREATE TABLE IF NOT EXISTS players (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    username VARCHAR(255) NOT NULL UNIQUE,
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    updated_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER)
+);
+
+CREATE TABLE IF NOT EXISTS beer_purchases (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    player_id UUID NOT NULL REFERENCES players (id) ON DELETE CASCADE,
+    beer_id UUID NOT NULL REFERENCES beers (id) ON DELETE CASCADE,
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    updated_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    UNIQUE (player_id, beer_id)
+);
+
+ALTER TABLE oil_platforms ADD COLUMN player_id UUID REFERENCES players (id) ON DELETE CASCADE;
+ALTER TABLE money_transactions ADD COLUMN player_id UUID REFERENCES players (id) ON DELETE CASCADE;
+
+-- hand the already existing single-player game over to a legacy player
+INSERT INTO players (username)
+SELECT 'player1'
+WHERE EXISTS (SELECT 1 FROM oil_platforms) OR EXISTS (SELECT 1 FROM money_transactions);
+
+UPDATE oil_platforms SET player_id = (SELECT id FROM players WHERE username = 'player1');
+UPDATE money_transactions SET player_id = (SELECT id FROM players WHERE username = 'player1');
+
+INSERT INTO beer_purchases (player_id, beer_id)
+SELECT players.id, beers.id FROM beers, players
+WHERE players.username = 'player1' AND beers.purchased = TRUE;
+
+ALTER TABLE oil_platforms ALTER COLUMN player_id SET NOT NULL;
+ALTER TABLE money_transactions ALTER COLUMN player_id SET NOT NULL;
+ALTER TABLE beers DROP COLUMN purchased;
+
+CREATE INDEX IF NOT EXISTS oil_platforms_player_id_idx ON oil_platforms (player_id);
+CREATE INDEX IF NOT EXISTS money_transactions_player_id_idx ON money_transactions (player_id);
@@ -0,0 +1,3 @@
+-- earnings are settled up to this point in time, missed periods are credited on the next settlement
+ALTER TABLE players
+ADD COLUMN last_settled_at BIGINT NOT NULL DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER);
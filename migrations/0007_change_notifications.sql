@@ -0,0 +1,38 @@
+-- notify listeners (the websocket app) about every change to a player's game entities
+CREATE OR REPLACE FUNCTION notify_game_change() RETURNS TRIGGER AS $$
+DECLARE
+    changed JSONB;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed := to_jsonb(OLD);
+    ELSE
+        changed := to_jsonb(NEW);
+    END IF;
+
+    -- only ids are sent, payloads are limited to 8000 bytes
+    PERFORM pg_notify('game_changes', jsonb_build_object(
+        'table', TG_TABLE_NAME,
+        'operation', TG_OP,
+        'id', changed->'id',
+        'player_id', changed->'player_id',
+        'beer_id', changed->'beer_id'
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS oil_platforms_notify ON oil_platforms;
+CREATE TRIGGER oil_platforms_notify
+AFTER INSERT OR UPDATE OR DELETE ON oil_platforms
+FOR EACH ROW EXECUTE FUNCTION notify_game_change();
+
+-- beers are shared by all players, purchases are what changes per player
+DROP TRIGGER IF EXISTS beer_purchases_notify ON beer_purchases;
+CREATE TRIGGER beer_purchases_notify
+AFTER INSERT ON beer_purchases
+FOR EACH ROW EXECUTE FUNCTION notify_game_change();
+
+DROP TRIGGER IF EXISTS money_transactions_notify ON money_transactions;
+CREATE TRIGGER money_transactions_notify
+AFTER INSERT ON money_transactions
+FOR EACH ROW EXECUTE FUNCTION notify_game_change();
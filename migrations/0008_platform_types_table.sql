@@ -0,0 +1,29 @@
+CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
+
+CREATE TABLE IF NOT EXISTS platform_types (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    cost BIGINT NOT NULL CHECK (cost >= 0),
+    upgrade_cost BIGINT NOT NULL CHECK (upgrade_cost >= 0),
+    -- profitability of a new platform, added again on every upgrade
+    profitability BIGINT NOT NULL CHECK (profitability >= 0),
+    max_level SMALLINT NOT NULL DEFAULT 10 CHECK (max_level >= 1),
+    image VARCHAR(1024) NOT NULL,
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    updated_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER)
+);
+
+-- the platform types that used to be hard-coded
+INSERT INTO platform_types (name, cost, upgrade_cost, profitability, max_level, image) VALUES
+    ('Rig', 1000, 100, 5, 10, 'https://upload.wikimedia.org/wikipedia/commons/a/ab/Oil_platform_P-51_%28Brazil%29.jpg'),
+    ('Ground', 10000, 500, 15, 10, 'https://www.ruralswalabama.org/wp-content/uploads/2011/11/Gilbertown-Oil-Well_1ce-866.jpg'),
+    ('Pump', 100000, 1000, 50, 10, 'https://images.fineartamerica.com/images/artworkimages/mediumlarge/2/2-oil-industry-well-pumps-pgiam.jpg')
+ON CONFLICT (name) DO NOTHING;
+
+-- types can be renamed, but not deleted while platforms of that type exist
+ALTER TABLE oil_platforms
+ADD CONSTRAINT oil_platforms_platform_type_fkey
+FOREIGN KEY (platform_type) REFERENCES platform_types (name) ON UPDATE CASCADE;
+
+-- admins manage platform types, grant with `UPDATE players SET is_admin = TRUE WHERE username = '...'`
+ALTER TABLE players ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,39 @@
+-- categorise ledger entries instead of guessing what item_id points at
+ALTER TABLE money_transactions
+ADD COLUMN kind VARCHAR(32) NOT NULL DEFAULT 'adjustment'
+    CHECK (kind IN ('starting_balance', 'earnings', 'platform_purchase', 'platform_upgrade', 'beer_purchase', 'refund', 'adjustment')),
+ADD COLUMN item_type VARCHAR(32) NOT NULL DEFAULT 'system'
+    CHECK (item_type IN ('system', 'platform', 'beer'));
+
+-- backfill existing entries, the first entry without an item is the starting balance
+UPDATE money_transactions SET kind = 'earnings'
+WHERE item_id = '00000000-0000-0000-0000-000000000000' AND reduces_balance = FALSE;
+
+UPDATE money_transactions t SET kind = 'starting_balance'
+WHERE t.id = (
+    SELECT f.id FROM money_transactions f
+    WHERE f.player_id = t.player_id AND f.item_id = '00000000-0000-0000-0000-000000000000'
+    ORDER BY f.created_at ASC, f.id ASC
+    LIMIT 1
+);
+
+UPDATE money_transactions SET kind = 'beer_purchase', item_type = 'beer'
+WHERE item_id IN (SELECT id FROM beers);
+
+-- platforms are paid for once and then upgraded, sold platforms are refunded
+UPDATE money_transactions t SET item_type = 'platform', kind = CASE
+    WHEN t.reduces_balance = FALSE THEN 'refund'
+    WHEN t.id = (
+        SELECT f.id FROM money_transactions f
+        WHERE f.item_id = t.item_id AND f.reduces_balance = TRUE
+        ORDER BY f.created_at ASC, f.id ASC
+        LIMIT 1
+    ) THEN 'platform_purchase'
+    ELSE 'platform_upgrade'
+END
+WHERE t.item_id <> '00000000-0000-0000-0000-000000000000'
+    AND t.item_id NOT IN (SELECT id FROM beers);
+
+-- new entries have to state what they are
+ALTER TABLE money_transactions ALTER COLUMN kind DROP DEFAULT;
+ALTER TABLE money_transactions ALTER COLUMN item_type DROP DEFAULT;
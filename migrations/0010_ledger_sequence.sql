@@ -0,0 +1,18 @@
+-- created_at only has second precision, entries booked in the same second
+-- are ordered by when they were inserted
+CREATE SEQUENCE IF NOT EXISTS money_transactions_seq_seq;
+ALTER TABLE money_transactions ADD COLUMN seq BIGINT;
+
+UPDATE money_transactions t SET seq = ordered.n
+FROM (
+    SELECT id, ROW_NUMBER() OVER (ORDER BY created_at ASC, ctid ASC) AS n
+    FROM money_transactions
+) ordered
+WHERE ordered.id = t.id;
+
+SELECT setval('money_transactions_seq_seq', COALESCE(MAX(seq), 0) + 1, false) FROM money_transactions;
+
+ALTER TABLE money_transactions
+ALTER COLUMN seq SET DEFAULT nextval('money_transactions_seq_seq'),
+ALTER COLUMN seq SET NOT NULL;
+ALTER SEQUENCE money_transactions_seq_seq OWNED BY money_transactions.seq;
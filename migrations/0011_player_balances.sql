@@ -0,0 +1,44 @@
+-- balance snapshot per player, kept in step with the ledger so that balance
+-- lookups do not have to sum every transaction
+CREATE TABLE IF NOT EXISTS player_balances (
+    player_id UUID PRIMARY KEY NOT NULL REFERENCES players (id) ON DELETE CASCADE,
+    balance BIGINT NOT NULL DEFAULT 0,
+    updated_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER)
+);
+
+INSERT INTO player_balances (player_id, balance)
+SELECT player_id, SUM(CASE WHEN reduces_balance = FALSE THEN amount ELSE -amount END)
+FROM money_transactions
+GROUP BY player_id
+ON CONFLICT (player_id) DO NOTHING;
+
+CREATE OR REPLACE FUNCTION apply_ledger_change() RETURNS TRIGGER AS $$
+BEGIN
+    -- updates are applied as removing the old entry and adding the new one
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE player_balances
+        SET balance = balance - CASE WHEN OLD.reduces_balance = FALSE THEN OLD.amount ELSE -OLD.amount END,
+            updated_at = CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER)
+        WHERE player_id = OLD.player_id;
+    END IF;
+
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        INSERT INTO player_balances (player_id, balance)
+        VALUES (NEW.player_id, CASE WHEN NEW.reduces_balance = FALSE THEN NEW.amount ELSE -NEW.amount END)
+        ON CONFLICT (player_id) DO UPDATE
+        SET balance = player_balances.balance + EXCLUDED.balance,
+            updated_at = CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER);
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS money_transactions_balance ON money_transactions;
+CREATE TRIGGER money_transactions_balance
+AFTER INSERT OR UPDATE OR DELETE ON money_transactions
+FOR EACH ROW EXECUTE FUNCTION apply_ledger_change();
+
+-- compaction and the transaction history scan a player's ledger by time
+CREATE INDEX IF NOT EXISTS money_transactions_player_created_idx
+ON money_transactions (player_id, created_at);
@@ -0,0 +1,17 @@
+-- which platforms an earnings entry was earned by, earnings settled before
+-- this migration cannot be attributed anymore
+CREATE TABLE IF NOT EXISTS platform_earnings (
+    transaction_id UUID NOT NULL REFERENCES money_transactions (id) ON DELETE CASCADE,
+    -- no foreign key, sold platforms keep their earnings history
+    platform_id UUID NOT NULL,
+    player_id UUID NOT NULL REFERENCES players (id) ON DELETE CASCADE,
+    amount BIGINT NOT NULL CHECK (amount >= 0),
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    PRIMARY KEY (transaction_id, platform_id)
+);
+
+CREATE INDEX IF NOT EXISTS platform_earnings_player_platform_idx
+ON platform_earnings (player_id, platform_id);
+
+-- lifetime investment per platform is looked up by item
+CREATE INDEX IF NOT EXISTS money_transactions_item_id_idx ON money_transactions (item_id);
@@ -0,0 +1,16 @@
+-- market events temporarily scale the earnings of every platform, or of the
+-- platforms of one type
+CREATE TABLE IF NOT EXISTS events (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    kind VARCHAR(32) NOT NULL
+        CHECK (kind IN ('oil_price_spike', 'storm', 'strike', 'windfall')),
+    -- NULL affects every platform type
+    platform_type VARCHAR(255) REFERENCES platform_types (name) ON UPDATE CASCADE ON DELETE CASCADE,
+    multiplier DOUBLE PRECISION NOT NULL CHECK (multiplier >= 0),
+    starts_at BIGINT NOT NULL,
+    ends_at BIGINT NOT NULL,
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS events_ends_at_idx ON events (ends_at);
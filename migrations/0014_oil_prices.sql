@@ -0,0 +1,6 @@
+-- oil price in dollars per barrel, recorded once per tick by the websocket
+-- app; platforms produce barrels and earn them at the price of the period
+CREATE TABLE IF NOT EXISTS oil_prices (
+    recorded_at BIGINT PRIMARY KEY NOT NULL,
+    price DOUBLE PRECISION NOT NULL CHECK (price > 0)
+);
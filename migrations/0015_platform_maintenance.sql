@@ -0,0 +1,15 @@
+-- running a platform costs its type's upkeep every tick
+ALTER TABLE platform_types ADD COLUMN upkeep BIGINT NOT NULL DEFAULT 0 CHECK (upkeep >= 0);
+
+UPDATE platform_types SET upkeep = 1 WHERE name = 'Rig';
+UPDATE platform_types SET upkeep = 3 WHERE name = 'Ground';
+UPDATE platform_types SET upkeep = 10 WHERE name = 'Pump';
+
+-- platforms wear down while running and break down at zero condition,
+-- producing nothing until repaired
+ALTER TABLE oil_platforms
+ADD COLUMN condition SMALLINT NOT NULL DEFAULT 100 CHECK (condition BETWEEN 0 AND 100);
+
+ALTER TABLE money_transactions DROP CONSTRAINT money_transactions_kind_check;
+ALTER TABLE money_transactions ADD CONSTRAINT money_transactions_kind_check
+    CHECK (kind IN ('starting_balance', 'earnings', 'upkeep', 'platform_purchase', 'platform_upgrade', 'platform_repair', 'beer_purchase', 'refund', 'adjustment'));
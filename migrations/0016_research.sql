@@ -0,0 +1,69 @@
+-- technologies are shared by all players, each one researched once per player
+CREATE TABLE IF NOT EXISTS technologies (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    description TEXT NOT NULL,
+    cost BIGINT NOT NULL CHECK (cost >= 0),
+    -- effects, a technology may have several
+    unlocks_platform_type VARCHAR(255) REFERENCES platform_types (name) ON UPDATE CASCADE ON DELETE SET NULL,
+    max_level_bonus SMALLINT NOT NULL DEFAULT 0 CHECK (max_level_bonus >= 0),
+    -- added to the player's earnings multiplier, 0.1 earns 10% more
+    profitability_bonus DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (profitability_bonus >= 0),
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    updated_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER)
+);
+
+CREATE TABLE IF NOT EXISTS technology_prerequisites (
+    technology_id UUID NOT NULL REFERENCES technologies (id) ON DELETE CASCADE,
+    prerequisite_id UUID NOT NULL REFERENCES technologies (id) ON DELETE CASCADE,
+    PRIMARY KEY (technology_id, prerequisite_id),
+    CHECK (technology_id <> prerequisite_id)
+);
+
+CREATE TABLE IF NOT EXISTS player_research (
+    player_id UUID NOT NULL REFERENCES players (id) ON DELETE CASCADE,
+    technology_id UUID NOT NULL REFERENCES technologies (id) ON DELETE CASCADE,
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    PRIMARY KEY (player_id, technology_id)
+);
+
+INSERT INTO technologies (name, description, cost, unlocks_platform_type, max_level_bonus, profitability_bonus) VALUES
+    ('Onshore drilling', 'Unlocks Ground platforms', 5000, 'Ground', 0, 0),
+    ('Oil refining', 'Platforms earn 10% more', 10000, NULL, 0, 0.1),
+    ('Reinforced structures', 'Platforms can be upgraded 2 more levels', 20000, NULL, 2, 0),
+    ('Pumpjack engineering', 'Unlocks Pump platforms', 50000, 'Pump', 0, 0),
+    ('Advanced refining', 'Platforms earn 25% more', 80000, NULL, 0, 0.25)
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO technology_prerequisites (technology_id, prerequisite_id)
+SELECT t.id, p.id
+FROM (VALUES
+    ('Reinforced structures', 'Onshore drilling'),
+    ('Pumpjack engineering', 'Onshore drilling'),
+    ('Advanced refining', 'Oil refining')
+) AS d (technology, prerequisite)
+JOIN technologies t ON t.name = d.technology
+JOIN technologies p ON p.name = d.prerequisite
+ON CONFLICT DO NOTHING;
+
+-- players keep access to the platform types they already own
+INSERT INTO player_research (player_id, technology_id)
+WITH RECURSIVE granted (player_id, technology_id) AS (
+    SELECT DISTINCT o.player_id, t.id
+    FROM oil_platforms o
+    JOIN technologies t ON t.unlocks_platform_type = o.platform_type
+    UNION
+    SELECT g.player_id, p.prerequisite_id
+    FROM granted g
+    JOIN technology_prerequisites p ON p.technology_id = g.technology_id
+)
+SELECT player_id, technology_id FROM granted
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE money_transactions DROP CONSTRAINT money_transactions_kind_check;
+ALTER TABLE money_transactions ADD CONSTRAINT money_transactions_kind_check
+    CHECK (kind IN ('starting_balance', 'earnings', 'upkeep', 'platform_purchase', 'platform_upgrade', 'platform_repair', 'beer_purchase', 'research', 'refund', 'adjustment'));
+
+ALTER TABLE money_transactions DROP CONSTRAINT money_transactions_item_type_check;
+ALTER TABLE money_transactions ADD CONSTRAINT money_transactions_item_type_check
+    CHECK (item_type IN ('system', 'platform', 'beer', 'technology'));
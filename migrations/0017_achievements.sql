@@ -0,0 +1,14 @@
+-- achievements are defined in the game config, only unlocks are stored
+CREATE TABLE IF NOT EXISTS player_achievements (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    player_id UUID NOT NULL REFERENCES players (id) ON DELETE CASCADE,
+    achievement VARCHAR(64) NOT NULL,
+    unlocked_at BIGINT NOT NULL DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    UNIQUE (player_id, achievement)
+);
+
+-- unlocks are pushed to the player's sockets like other entity changes
+DROP TRIGGER IF EXISTS player_achievements_notify ON player_achievements;
+CREATE TRIGGER player_achievements_notify
+AFTER INSERT ON player_achievements
+FOR EACH ROW EXECUTE FUNCTION notify_game_change();
@@ -0,0 +1,41 @@
+-- one record per player that bought every beer, ranked on the leaderboard;
+-- totals are taken from the ledger up to the final beer purchase
+CREATE TABLE IF NOT EXISTS completed_games (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    player_id UUID NOT NULL UNIQUE REFERENCES players (id) ON DELETE CASCADE,
+    -- first ledger entry, i.e. the starting balance
+    started_at BIGINT NOT NULL,
+    completed_at BIGINT NOT NULL,
+    total_earned BIGINT NOT NULL,
+    total_spent BIGINT NOT NULL,
+    beers_spent BIGINT NOT NULL,
+    platforms_bought BIGINT NOT NULL,
+    platforms_owned BIGINT NOT NULL,
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER),
+    CHECK (completed_at >= started_at)
+);
+
+CREATE INDEX IF NOT EXISTS completed_games_duration_idx
+ON completed_games ((completed_at - started_at));
+
+-- record the games won before the leaderboard existed, platforms sold
+-- since are not known anymore and current ones are counted instead
+INSERT INTO completed_games (
+    player_id, started_at, completed_at, total_earned, total_spent, beers_spent,
+    platforms_bought, platforms_owned
+)
+SELECT w.player_id, MIN(t.created_at), w.completed_at,
+    CAST(COALESCE(SUM(t.amount) FILTER (WHERE NOT t.reduces_balance), 0) AS BIGINT),
+    CAST(COALESCE(SUM(t.amount) FILTER (WHERE t.reduces_balance), 0) AS BIGINT),
+    CAST(COALESCE(SUM(t.amount) FILTER (WHERE t.kind = 'beer_purchase'), 0) AS BIGINT),
+    COUNT(*) FILTER (WHERE t.kind = 'platform_purchase'),
+    (SELECT COUNT(*) FROM oil_platforms WHERE player_id = w.player_id)
+FROM (
+    SELECT bp.player_id, MAX(bp.created_at) AS completed_at
+    FROM beer_purchases bp
+    GROUP BY bp.player_id
+    HAVING COUNT(*) = (SELECT COUNT(*) FROM beers) AND COUNT(*) > 0
+) w
+JOIN money_transactions t ON t.player_id = w.player_id AND t.created_at <= w.completed_at
+GROUP BY w.player_id, w.completed_at
+ON CONFLICT (player_id) DO NOTHING;
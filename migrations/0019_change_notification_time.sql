@@ -0,0 +1,24 @@
+-- notifications carry the time of the change, so that achievements with a
+-- time limit are judged by when the change was made, not when it was checked
+CREATE OR REPLACE FUNCTION notify_game_change() RETURNS TRIGGER AS $$
+DECLARE
+    changed JSONB;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed := to_jsonb(OLD);
+    ELSE
+        changed := to_jsonb(NEW);
+    END IF;
+
+    -- only ids are sent, payloads are limited to 8000 bytes
+    PERFORM pg_notify('game_changes', jsonb_build_object(
+        'table', TG_TABLE_NAME,
+        'operation', TG_OP,
+        'id', changed->'id',
+        'player_id', changed->'player_id',
+        'beer_id', changed->'beer_id',
+        'at', CAST(EXTRACT(EPOCH FROM NOW()) AS BIGINT)
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,12 @@
+-- single-use tickets for opening a websocket, so that session tokens are
+-- neither embedded in pages nor sent in the socket url
+CREATE TABLE IF NOT EXISTS socket_tickets (
+    id UUID PRIMARY KEY NOT NULL DEFAULT (uuid_generate_v4()),
+    player_id UUID NOT NULL REFERENCES players (id) ON DELETE CASCADE,
+    ticket_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT DEFAULT CAST(EXTRACT(EPOCH FROM NOW()) AS INTEGER)
+);
+
+CREATE INDEX IF NOT EXISTS sessions_expires_at_idx ON sessions (expires_at);
+CREATE INDEX IF NOT EXISTS socket_tickets_expires_at_idx ON socket_tickets (expires_at);
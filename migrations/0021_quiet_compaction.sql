@@ -0,0 +1,28 @@
+-- ledger compaction only rewrites history, transactions that set
+-- app.quiet_changes for themselves do not notify the websocket app
+CREATE OR REPLACE FUNCTION notify_game_change() RETURNS TRIGGER AS $$
+DECLARE
+    changed JSONB;
+BEGIN
+    IF current_setting('app.quiet_changes', TRUE) = 'on' THEN
+        RETURN NULL;
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        changed := to_jsonb(OLD);
+    ELSE
+        changed := to_jsonb(NEW);
+    END IF;
+
+    -- only ids are sent, payloads are limited to 8000 bytes
+    PERFORM pg_notify('game_changes', jsonb_build_object(
+        'table', TG_TABLE_NAME,
+        'operation', TG_OP,
+        'id', changed->'id',
+        'player_id', changed->'player_id',
+        'beer_id', changed->'beer_id',
+        'at', CAST(EXTRACT(EPOCH FROM NOW()) AS BIGINT)
+    )::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
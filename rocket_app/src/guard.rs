@@ -0,0 +1,107 @@
+use rocket::{
+    http::Status,
+    request::{FromRequest, Outcome},
+    Request, State,
+};
+use shared_db::repo::session_repo::SessionError;
+use uuid::Uuid;
+
+use crate::AppRepositories;
+
+pub const SESSION_COOKIE: &str = "session_token";
+
+/// The logged in player whose game the request operates on. API clients
+/// authenticate with an `Authorization: Bearer <token>` header, the UI with
+/// a private session cookie.
+pub struct CurrentPlayer {
+    pub id: Uuid,
+    pub username: String,
+    pub is_admin: bool,
+    pub token: String,
+}
+
+/// A single-use ticket for the page's websocket, issued to the logged in
+/// player so that the session token never ends up in the page.
+pub struct SocketTicket(pub String);
+
+/// A logged in player with admin rights, other players get a 403.
+pub struct AdminPlayer {
+    pub username: String,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for CurrentPlayer {
+    type Error = SessionError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let token = match req.headers().get_one("Authorization") {
+            Some(header) => match header.strip_prefix("Bearer ") {
+                Some(token) => token.trim().to_string(),
+                None => return Outcome::Error((Status::Unauthorized, SessionError::NotFound)),
+            },
+            None => match req.cookies().get_private(SESSION_COOKIE) {
+                Some(cookie) => cookie.value().to_string(),
+                None => return Outcome::Error((Status::Unauthorized, SessionError::NotFound)),
+            },
+        };
+
+        let data = match req.guard::<&State<AppRepositories>>().await {
+            Outcome::Success(data) => data,
+            _ => return Outcome::Error((Status::InternalServerError, SessionError::OtherError)),
+        };
+        match data.session_repo.get_player_by_token(&token).await {
+            Ok(player) => Outcome::Success(CurrentPlayer {
+                id: player.id,
+                username: player.username,
+                is_admin: player.is_admin,
+                token,
+            }),
+            Err(SessionError::OtherError) => {
+                Outcome::Error((Status::InternalServerError, SessionError::OtherError))
+            }
+            Err(e) => Outcome::Error((Status::Unauthorized, e)),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SocketTicket {
+    type Error = SessionError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let player = match req.guard::<CurrentPlayer>().await {
+            Outcome::Success(player) => player,
+            Outcome::Error(e) => return Outcome::Error(e),
+            Outcome::Forward(status) => return Outcome::Forward(status),
+        };
+
+        let data = match req.guard::<&State<AppRepositories>>().await {
+            Outcome::Success(data) => data,
+            _ => return Outcome::Error((Status::InternalServerError, SessionError::OtherError)),
+        };
+        match data.session_repo.create_socket_ticket(player.id).await {
+            Ok(ticket) => Outcome::Success(SocketTicket(ticket)),
+            Err(e) => Outcome::Error((Status::InternalServerError, e)),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminPlayer {
+    type Error = SessionError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let player = match req.guard::<CurrentPlayer>().await {
+            Outcome::Success(player) => player,
+            Outcome::Error(e) => return Outcome::Error(e),
+            Outcome::Forward(status) => return Outcome::Forward(status),
+        };
+
+        if !player.is_admin {
+            return Outcome::Error((Status::Forbidden, SessionError::NotFound));
+        }
+        Outcome::Success(AdminPlayer {
+            username: player.username,
+        })
+    }
+}
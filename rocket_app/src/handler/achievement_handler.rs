@@ -0,0 +1,33 @@
+use crate::{guard::CurrentPlayer, schema::AchievementListResponse, AppRepositories};
+use rocket::{get, http::Status, serde::json::Json, State};
+
+#[get("/achievements")]
+pub async fn achievements_list_handler(
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<AchievementListResponse>, Status> {
+    let achievement_repo = &data.achievement_repo;
+    let achievement_service = &data.achievement_service;
+
+    // catch up on rules met while the websocket app was not running
+    achievement_service
+        .evaluate(player.id, chrono::Utc::now().timestamp())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let achievements = achievement_repo
+        .get_all(player.id)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let json_response = AchievementListResponse {
+        status: "success".to_string(),
+        results: achievements.len(),
+        unlocked: achievements
+            .iter()
+            .filter(|achievement| achievement.unlocked_at.is_some())
+            .count(),
+        achievements,
+    };
+    Ok(Json(json_response))
+}
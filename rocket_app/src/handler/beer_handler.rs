@@ -1,19 +1,21 @@
 use crate::{
+    guard::CurrentPlayer,
     schema::{BeerListResponse, GenericResponse, SingleBeerResponse},
     AppRepositories,
 };
 use rocket::{get, http::Status, response::status::Custom, serde::json::Json, State};
-use shared_db::{model::CreateMoneyTransactionModel, repo::beer_repo::BeerError};
+use shared_db::service::purchase_service::PurchaseError;
 use uuid::Uuid;
 
 #[get("/beers")]
 pub async fn beers_list_handler(
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
 ) -> Result<Json<BeerListResponse>, Status> {
     let beers_repo = &data.beer_repo;
 
     let beers = beers_repo
-        .get_all()
+        .get_all(player.id)
         .await
         .map_err(|_| Status::InternalServerError)?;
 
@@ -28,6 +30,7 @@ pub async fn beers_list_handler(
 #[patch("/beers/<id>")]
 pub async fn purchase_beer_handler(
     id: String,
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
 ) -> Result<Json<SingleBeerResponse>, Custom<Json<GenericResponse>>> {
     // validate provided id
@@ -45,52 +48,21 @@ pub async fn purchase_beer_handler(
         }
     };
 
-    // check if exists
-    let beer_repo = &data.beer_repo;
-    let retrieved = match beer_repo.get_by_id(uuid).await {
+    // purchase beer and debit its cost atomically
+    let purchase_service = &data.purchase_service;
+    let updated = match purchase_service.purchase_beer(player.id, uuid).await {
         Ok(beer) => beer,
-        Err(e) => {
-            let error_response = Custom(
-                Status::InternalServerError,
-                Json(GenericResponse {
-                    status: "error".to_string(),
-                    message: e.to_string(),
-                }),
-            );
-            return Err(error_response);
-        }
-    };
-
-    // validate amount of available money
-    let finances_repo = &data.finances_repo;
-    let balance = match finances_repo.get_available_balance().await {
-        Ok(bal) => bal,
-        Err(_) => {
+        Err(PurchaseError::InsufficientFunds) => {
             let error_response = Custom(
-                Status::InternalServerError,
+                Status::BadRequest,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: "Failed to upgrade beer".to_string(),
+                    message: "Not enough funds for purchase".to_string(),
                 }),
             );
             return Err(error_response);
         }
-    };
-    if retrieved.cost > balance {
-        let error_response = Custom(
-            Status::BadRequest,
-            Json(GenericResponse {
-                status: "error".to_string(),
-                message: "Not enough funds for purchase".to_string(),
-            }),
-        );
-        return Err(error_response);
-    }
-
-    // update and save
-    let updated = match beer_repo.purchase(uuid).await {
-        Ok(platform) => platform,
-        Err(BeerError::AlreadyPurchased) => {
+        Err(PurchaseError::AlreadyPurchased) => {
             let error_response = Custom(
                 Status::BadRequest,
                 Json(GenericResponse {
@@ -100,32 +72,22 @@ pub async fn purchase_beer_handler(
             );
             return Err(error_response);
         }
-        Err(e) => {
+        Err(PurchaseError::NotFound) => {
             let error_response = Custom(
-                Status::InternalServerError,
+                Status::NotFound,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: e.to_string(),
+                    message: "Beer not found".to_string(),
                 }),
             );
             return Err(error_response);
         }
-    };
-
-    // create associated money transaction
-    let platform_update_tx = CreateMoneyTransactionModel {
-        item_id: Some(updated.id),
-        amount: updated.cost,
-        reduces_balance: true,
-    };
-    match finances_repo.create(platform_update_tx).await {
-        Ok(_) => (),
         Err(e) => {
             let error_response = Custom(
                 Status::InternalServerError,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: format!("Failed to create platform: {}", e.to_string()),
+                    message: e.to_string(),
                 }),
             );
             return Err(error_response);
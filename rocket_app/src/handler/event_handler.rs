@@ -0,0 +1,22 @@
+use crate::{guard::CurrentPlayer, schema::MarketEventListResponse, AppRepositories};
+use rocket::{get, http::Status, serde::json::Json, State};
+
+#[get("/events")]
+pub async fn market_events_list_handler(
+    _player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<MarketEventListResponse>, Status> {
+    let event_repo = &data.event_repo;
+
+    let events = event_repo
+        .get_active(chrono::Utc::now().timestamp())
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let json_response = MarketEventListResponse {
+        status: "success".to_string(),
+        results: events.len(),
+        events,
+    };
+    Ok(Json(json_response))
+}
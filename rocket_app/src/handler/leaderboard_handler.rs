@@ -0,0 +1,67 @@
+use crate::{
+    guard::CurrentPlayer,
+    helper::leaderboard_order,
+    schema::{GenericResponse, LeaderboardQuerySchema, LeaderboardResponse},
+    AppRepositories,
+};
+
+use shared_db::{constants::LEADERBOARD_SIZE, repo::leaderboard_repo::LeaderboardError};
+
+use rocket::{get, http::Status, response::status::Custom, serde::json::Json, State};
+
+#[get("/leaderboard?<query..>")]
+pub async fn leaderboard_handler(
+    query: LeaderboardQuerySchema,
+    _player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<LeaderboardResponse>, Custom<Json<GenericResponse>>> {
+    let order = match leaderboard_order(&query) {
+        Ok(res) => res,
+        Err(message) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message,
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let leaderboard_repo = &data.leaderboard_repo;
+    let entries = match leaderboard_repo
+        .get_ranking(order, query.limit.unwrap_or(LEADERBOARD_SIZE))
+        .await
+    {
+        Ok(res) => res,
+        Err(e @ LeaderboardError::InvalidLimit) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to load leaderboard: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let json_response = LeaderboardResponse {
+        status: "success".to_string(),
+        results: entries.len(),
+        sort: order,
+        entries,
+    };
+    Ok(Json(json_response))
+}
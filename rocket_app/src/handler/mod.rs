@@ -1,2 +1,11 @@
+pub mod achievement_handler;
 pub mod beer_handler;
+pub mod event_handler;
+pub mod leaderboard_handler;
+pub mod oil_price_handler;
 pub mod platform_handler;
+pub mod platform_type_handler;
+pub mod player_handler;
+pub mod research_handler;
+pub mod stats_handler;
+pub mod transaction_handler;
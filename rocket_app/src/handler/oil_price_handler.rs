@@ -0,0 +1,71 @@
+use crate::{
+    guard::CurrentPlayer,
+    schema::{GenericResponse, OilPriceHistoryResponse, OilPriceQuerySchema},
+    AppRepositories,
+};
+
+use shared_db::{config::game_config, repo::oil_price_repo::OilPriceError};
+
+use rocket::{get, http::Status, response::status::Custom, serde::json::Json, State};
+
+#[get("/oil-prices?<query..>")]
+pub async fn oil_prices_list_handler(
+    query: OilPriceQuerySchema,
+    _player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<OilPriceHistoryResponse>, Custom<Json<GenericResponse>>> {
+    let oil_price_repo = &data.oil_price_repo;
+
+    let to = query.to.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let prices = match query.from.or(to.checked_sub(3600)) {
+        Some(from) => oil_price_repo.get_history(from, to).await,
+        None => Err(OilPriceError::InvalidRange),
+    };
+    let prices = match prices {
+        Ok(res) => res,
+        Err(OilPriceError::InvalidRange) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Invalid time range provided".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to load oil prices: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    // the series may be empty before the first tick
+    let current_price = match oil_price_repo.get_latest().await {
+        Ok(Some(latest)) => latest.price,
+        Ok(None) => game_config().oil_market.base_price,
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to load oil prices: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let json_response = OilPriceHistoryResponse {
+        status: "success".to_string(),
+        results: prices.len(),
+        current_price,
+        prices,
+    };
+    Ok(Json(json_response))
+}
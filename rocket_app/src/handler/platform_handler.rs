@@ -1,15 +1,14 @@
-use std::panic;
-
 use crate::{
-    helper::{get_platform_cost, get_platform_profitability, get_platform_upgrade_cost},
-    schema::{CreatePlatformSchema, GenericResponse, PlatformListResponse, SinglePlatformResponse},
+    guard::CurrentPlayer,
+    schema::{
+        BulkPurchaseResponse, BulkPurchaseSchema, BulkUpgradeResponse, BulkUpgradeSchema,
+        CreatePlatformSchema, GenericResponse, PlatformListResponse, PlatformRepairResponse,
+        PlatformSaleResponse, SinglePlatformResponse,
+    },
     AppRepositories,
 };
 
-use shared_db::{
-    model::{CreateMoneyTransactionModel, CreatePlatformModel, UpdatePlatformModel},
-    repo::{generic::Repo, platform_repo::OilPlatformError},
-};
+use shared_db::{model::PlatformOverviewModel, service::purchase_service::PurchaseError};
 
 use rocket::{get, http::Status, post, response::status::Custom, serde::json::Json, State};
 use uuid::Uuid;
@@ -25,12 +24,13 @@ pub async fn health_checker_handler() -> Result<Json<GenericResponse>, Status> {
 
 #[get("/platforms")]
 pub async fn platforms_list_handler(
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
 ) -> Result<Json<PlatformListResponse>, Status> {
     let oil_platform_repo = &data.platform_repo;
 
     let platforms = oil_platform_repo
-        .get_all()
+        .get_overviews(player.id)
         .await
         .map_err(|_| Status::InternalServerError)?;
 
@@ -45,99 +45,129 @@ pub async fn platforms_list_handler(
 #[post("/platforms", data = "<body>")]
 pub async fn create_platform_handler(
     body: Json<CreatePlatformSchema>,
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
 ) -> Result<Json<SinglePlatformResponse>, Custom<Json<GenericResponse>>> {
     let create_request = body.into_inner();
 
-    // validate platform type
-    let platform_type_ref: &str = &create_request.platform_type;
-    let result = panic::catch_unwind(|| platform_type_ref.into());
-
-    let validated_platform_type = match result {
-        Ok(validated) => validated,
-        Err(e) => {
-            println!("Panic occurred: {:?}", e);
+    // purchase platform and debit its cost atomically
+    let purchase_service = &data.purchase_service;
+    let created = match purchase_service
+        .purchase_platform(player.id, &create_request.platform_type)
+        .await
+    {
+        Ok(platform) => platform,
+        Err(e @ (PurchaseError::UnknownPlatformType | PurchaseError::NotResearched)) => {
             let error_response = Custom(
                 Status::BadRequest,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: "Invalid platform type specified".to_string(),
+                    message: e.to_string(),
                 }),
             );
             return Err(error_response);
         }
-    };
-
-    // validate amount of available money
-    let finances_repo = &data.finances_repo;
-    let balance = match finances_repo.get_available_balance().await {
-        Ok(bal) => bal,
-        Err(_) => {
+        Err(PurchaseError::InsufficientFunds) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Not enough funds for purchase".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
             let error_response = Custom(
                 Status::InternalServerError,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: "Failed to create platform".to_string(),
+                    message: format!("Failed to create platform: {}", e),
                 }),
             );
             return Err(error_response);
         }
     };
-    let cost = get_platform_cost(validated_platform_type);
-    if cost > balance {
-        let error_response = Custom(
-            Status::BadRequest,
-            Json(GenericResponse {
-                status: "error".to_string(),
-                message: "Not enough funds for purchase".to_string(),
-            }),
-        );
-        return Err(error_response);
-    }
 
-    // save to database
-    let platform_create = CreatePlatformModel {
-        platform_type: validated_platform_type,
-        profitability: get_platform_profitability(validated_platform_type),
+    let overview = platform_overview(data, player.id, created.id).await?;
+
+    let json_response = SinglePlatformResponse {
+        status: "success".to_string(),
+        data: overview,
     };
-    let oil_platform_repo = &data.platform_repo;
-    let created = match oil_platform_repo.create(platform_create).await {
-        Ok(platform) => platform,
-        Err(_) => {
+    Ok(Json(json_response))
+}
+
+#[post("/platforms/bulk", data = "<body>")]
+pub async fn bulk_create_platforms_handler(
+    body: Json<BulkPurchaseSchema>,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<BulkPurchaseResponse>, Custom<Json<GenericResponse>>> {
+    let bulk_request = body.into_inner();
+
+    // purchase as many platforms as affordable and debit them atomically
+    let purchase_service = &data.purchase_service;
+    let purchase = match purchase_service
+        .purchase_platforms(player.id, &bulk_request.platform_type, bulk_request.count)
+        .await
+    {
+        Ok(purchase) => purchase,
+        Err(
+            e @ (PurchaseError::UnknownPlatformType
+            | PurchaseError::NotResearched
+            | PurchaseError::InvalidQuantity
+            | PurchaseError::InsufficientFunds),
+        ) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
             let error_response = Custom(
                 Status::InternalServerError,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: "Failed to create platform".to_string(),
+                    message: format!("Failed to create platforms: {}", e),
                 }),
             );
             return Err(error_response);
         }
     };
 
-    // create associated money transaction
-    let platform_create_tx = CreateMoneyTransactionModel {
-        item_id: Some(created.id),
-        amount: cost,
-        reduces_balance: true,
-    };
-    match finances_repo.create(platform_create_tx).await {
-        Ok(_) => (),
+    let platforms = match data.platform_repo.get_overviews(player.id).await {
+        Ok(overviews) => overviews
+            .into_iter()
+            .filter(|overview| {
+                purchase
+                    .platforms
+                    .iter()
+                    .any(|platform| platform.id == overview.platform.id)
+            })
+            .collect::<Vec<_>>(),
         Err(e) => {
             let error_response = Custom(
                 Status::InternalServerError,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: format!("Failed to create platform: {}", e.to_string()),
+                    message: e.to_string(),
                 }),
             );
             return Err(error_response);
         }
     };
 
-    let json_response = SinglePlatformResponse {
+    let json_response = BulkPurchaseResponse {
         status: "success".to_string(),
-        data: created,
+        requested: purchase.requested,
+        purchased: platforms.len(),
+        total_cost: purchase.total_cost,
+        platforms,
     };
     Ok(Json(json_response))
 }
@@ -145,6 +175,7 @@ pub async fn create_platform_handler(
 #[patch("/platforms/<id>")]
 pub async fn edit_platform_handler(
     id: String,
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
 ) -> Result<Json<SinglePlatformResponse>, Custom<Json<GenericResponse>>> {
     // validate provided id
@@ -162,10 +193,40 @@ pub async fn edit_platform_handler(
         }
     };
 
-    // check if exists
-    let oil_platform_repo = &data.platform_repo;
-    let retrieved = match oil_platform_repo.get_by_id(uuid).await {
+    // upgrade platform and debit its cost atomically
+    let purchase_service = &data.purchase_service;
+    let updated = match purchase_service.upgrade_platform(player.id, uuid).await {
         Ok(platform) => platform,
+        Err(e @ PurchaseError::NotFound) => {
+            let error_response = Custom(
+                Status::NotFound,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(PurchaseError::InsufficientFunds) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Not enough funds for purchase".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(PurchaseError::MaxLevelReached) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "You have already upgraded the platform to the maximum".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
         Err(e) => {
             let error_response = Custom(
                 Status::InternalServerError,
@@ -178,45 +239,133 @@ pub async fn edit_platform_handler(
         }
     };
 
-    // validate amount of available money
-    let finances_repo = &data.finances_repo;
-    let balance = match finances_repo.get_available_balance().await {
-        Ok(bal) => bal,
+    let overview = platform_overview(data, player.id, updated.id).await?;
+
+    let json_response = SinglePlatformResponse {
+        status: "success".to_string(),
+        data: overview,
+    };
+    Ok(Json(json_response))
+}
+
+#[patch("/platforms/<id>/levels", data = "<body>")]
+pub async fn upgrade_platform_levels_handler(
+    id: String,
+    body: Json<BulkUpgradeSchema>,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<BulkUpgradeResponse>, Custom<Json<GenericResponse>>> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
         Err(_) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Invalid ID provided".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    // upgrade as many levels as affordable and debit them atomically
+    let purchase_service = &data.purchase_service;
+    let upgrade = match purchase_service
+        .upgrade_platform_levels(player.id, uuid, body.into_inner().levels)
+        .await
+    {
+        Ok(upgrade) => upgrade,
+        Err(e @ PurchaseError::NotFound) => {
+            let error_response = Custom(
+                Status::NotFound,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(
+            e @ (PurchaseError::InvalidQuantity
+            | PurchaseError::InsufficientFunds
+            | PurchaseError::MaxLevelReached),
+        ) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
             let error_response = Custom(
                 Status::InternalServerError,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: "Failed to upgrade platform".to_string(),
+                    message: e.to_string(),
                 }),
             );
             return Err(error_response);
         }
     };
-    let cost = get_platform_upgrade_cost(retrieved.platform_type);
-    if cost > balance {
-        let error_response = Custom(
-            Status::BadRequest,
-            Json(GenericResponse {
-                status: "error".to_string(),
-                message: "Not enough funds for purchase".to_string(),
-            }),
-        );
-        return Err(error_response);
-    }
 
-    // update and save
-    let platform_update = UpdatePlatformModel {
-        profitability_addition: get_platform_profitability(retrieved.platform_type),
+    let overview = platform_overview(data, player.id, upgrade.platform.id).await?;
+
+    let json_response = BulkUpgradeResponse {
+        status: "success".to_string(),
+        requested: upgrade.requested,
+        upgraded: upgrade.levels,
+        total_cost: upgrade.total_cost,
+        data: overview,
     };
-    let updated = match oil_platform_repo.update(uuid, platform_update).await {
-        Ok(platform) => platform,
-        Err(OilPlatformError::MaxLevelReached) => {
+    Ok(Json(json_response))
+}
+
+#[post("/platforms/<id>/repair")]
+pub async fn repair_platform_handler(
+    id: String,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<PlatformRepairResponse>, Custom<Json<GenericResponse>>> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
+        Err(_) => {
             let error_response = Custom(
                 Status::BadRequest,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: "You have already upgraded the platform to the maximum".to_string(),
+                    message: "Invalid ID provided".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    // restore the condition and debit the repair atomically
+    let purchase_service = &data.purchase_service;
+    let repair = match purchase_service.repair_platform(player.id, uuid).await {
+        Ok(repair) => repair,
+        Err(e @ PurchaseError::NotFound) => {
+            let error_response = Custom(
+                Status::NotFound,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e @ (PurchaseError::InsufficientFunds | PurchaseError::NothingToRepair)) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
                 }),
             );
             return Err(error_response);
@@ -226,36 +375,95 @@ pub async fn edit_platform_handler(
                 Status::InternalServerError,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: e.to_string(),
+                    message: format!("Failed to repair platform: {}", e),
                 }),
             );
             return Err(error_response);
         }
     };
 
-    // create associated money transaction
-    let platform_update_tx = CreateMoneyTransactionModel {
-        item_id: Some(updated.id),
-        amount: cost,
-        reduces_balance: true,
+    let overview = platform_overview(data, player.id, repair.platform.id).await?;
+
+    let json_response = PlatformRepairResponse {
+        status: "success".to_string(),
+        cost: repair.cost,
+        data: overview,
+    };
+    Ok(Json(json_response))
+}
+
+#[delete("/platforms/<id>")]
+pub async fn sell_platform_handler(
+    id: String,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<PlatformSaleResponse>, Custom<Json<GenericResponse>>> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
+        Err(_) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Invalid ID provided".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
     };
-    match finances_repo.create(platform_update_tx).await {
-        Ok(_) => (),
+
+    // demolish platform and credit the refund atomically
+    let purchase_service = &data.purchase_service;
+    let sale = match purchase_service.sell_platform(player.id, uuid).await {
+        Ok(sale) => sale,
+        Err(e @ PurchaseError::NotFound) => {
+            let error_response = Custom(
+                Status::NotFound,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
         Err(e) => {
             let error_response = Custom(
                 Status::InternalServerError,
                 Json(GenericResponse {
                     status: "error".to_string(),
-                    message: format!("Failed to create platform: {}", e.to_string()),
+                    message: format!("Failed to sell platform: {}", e),
                 }),
             );
             return Err(error_response);
         }
     };
 
-    let json_response = SinglePlatformResponse {
+    let json_response = PlatformSaleResponse {
         status: "success".to_string(),
-        data: updated,
+        refund: sale.refund,
+        data: sale.platform,
     };
     Ok(Json(json_response))
 }
+
+// returns the platform with its earnings after a purchase or an upgrade
+async fn platform_overview(
+    data: &State<AppRepositories>,
+    player_id: Uuid,
+    id: Uuid,
+) -> Result<PlatformOverviewModel, Custom<Json<GenericResponse>>> {
+    match data.platform_repo.get_overview(player_id, id).await {
+        Ok(overview) => Ok(overview),
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            Err(error_response)
+        }
+    }
+}
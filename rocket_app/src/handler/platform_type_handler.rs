@@ -0,0 +1,145 @@
+use crate::{
+    guard::{AdminPlayer, CurrentPlayer},
+    schema::{
+        CreatePlatformTypeSchema, GenericResponse, PlatformTypeListResponse,
+        SinglePlatformTypeResponse, UpdatePlatformTypeSchema,
+    },
+    AppRepositories,
+};
+use rocket::{
+    delete, get, http::Status, patch, post, response::status::Custom, serde::json::Json, Request,
+    State,
+};
+use shared_db::{
+    model::{CreatePlatformTypeModel, UpdatePlatformTypeModel},
+    repo::platform_type_repo::PlatformTypeError,
+};
+
+#[get("/platform-types")]
+pub async fn platform_types_list_handler(
+    _player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<PlatformTypeListResponse>, Status> {
+    let platform_type_repo = &data.platform_type_repo;
+
+    let platform_types = platform_type_repo
+        .get_all()
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let json_response = PlatformTypeListResponse {
+        status: "success".to_string(),
+        results: platform_types.len(),
+        platform_types,
+    };
+    Ok(Json(json_response))
+}
+
+#[post("/admin/platform-types", data = "<body>")]
+pub async fn create_platform_type_handler(
+    body: Json<CreatePlatformTypeSchema>,
+    admin: AdminPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<SinglePlatformTypeResponse>, Custom<Json<GenericResponse>>> {
+    let create_request = body.into_inner();
+
+    let platform_type_repo = &data.platform_type_repo;
+    let platform_type_create = CreatePlatformTypeModel {
+        name: create_request.name,
+        cost: create_request.cost,
+        upgrade_cost: create_request.upgrade_cost,
+        profitability: create_request.profitability,
+        max_level: create_request.max_level,
+        image: create_request.image,
+        upkeep: create_request.upkeep,
+    };
+    let created = match platform_type_repo.create(platform_type_create).await {
+        Ok(platform_type) => platform_type,
+        Err(e) => return Err(platform_type_error_response(e)),
+    };
+    println!(
+        "Platform type {} created by {}",
+        created.name, admin.username
+    );
+
+    let json_response = SinglePlatformTypeResponse {
+        status: "success".to_string(),
+        data: created,
+    };
+    Ok(Json(json_response))
+}
+
+#[patch("/admin/platform-types/<name>", data = "<body>")]
+pub async fn edit_platform_type_handler(
+    name: String,
+    body: Json<UpdatePlatformTypeSchema>,
+    admin: AdminPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<SinglePlatformTypeResponse>, Custom<Json<GenericResponse>>> {
+    let update_request = body.into_inner();
+
+    let platform_type_repo = &data.platform_type_repo;
+    let platform_type_update = UpdatePlatformTypeModel {
+        name: update_request.name,
+        cost: update_request.cost,
+        upgrade_cost: update_request.upgrade_cost,
+        profitability: update_request.profitability,
+        max_level: update_request.max_level,
+        image: update_request.image,
+        upkeep: update_request.upkeep,
+    };
+    let updated = match platform_type_repo.update(&name, platform_type_update).await {
+        Ok(platform_type) => platform_type,
+        Err(e) => return Err(platform_type_error_response(e)),
+    };
+    println!("Platform type {} updated by {}", name, admin.username);
+
+    let json_response = SinglePlatformTypeResponse {
+        status: "success".to_string(),
+        data: updated,
+    };
+    Ok(Json(json_response))
+}
+
+#[delete("/admin/platform-types/<name>")]
+pub async fn delete_platform_type_handler(
+    name: String,
+    admin: AdminPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<GenericResponse>, Custom<Json<GenericResponse>>> {
+    let platform_type_repo = &data.platform_type_repo;
+    if let Err(e) = platform_type_repo.delete(&name).await {
+        return Err(platform_type_error_response(e));
+    }
+    println!("Platform type {} deleted by {}", name, admin.username);
+
+    let json_response = GenericResponse {
+        status: "success".to_string(),
+        message: format!("platform type {} deleted", name),
+    };
+    Ok(Json(json_response))
+}
+
+#[catch(403)]
+pub fn forbidden_catcher(_req: &Request) -> Json<GenericResponse> {
+    Json(GenericResponse {
+        status: "error".to_string(),
+        message: "Only admins may manage platform types".to_string(),
+    })
+}
+
+fn platform_type_error_response(e: PlatformTypeError) -> Custom<Json<GenericResponse>> {
+    let status = match e {
+        PlatformTypeError::NotFound => Status::NotFound,
+        PlatformTypeError::NameTaken | PlatformTypeError::InUse => Status::Conflict,
+        PlatformTypeError::InvalidValue(_) => Status::BadRequest,
+        PlatformTypeError::OtherError => Status::InternalServerError,
+    };
+    Custom(
+        status,
+        Json(GenericResponse {
+            status: "error".to_string(),
+            message: e.to_string(),
+        }),
+    )
+}
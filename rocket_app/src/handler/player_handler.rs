@@ -0,0 +1,201 @@
+use crate::{
+    guard::CurrentPlayer,
+    schema::{
+        CredentialsSchema, GenericResponse, SessionResponse, SinglePlayerResponse,
+        SocketTicketResponse,
+    },
+    AppRepositories,
+};
+use rocket::{
+    get, http::Status, post, response::status::Custom, serde::json::Json, Request, State,
+};
+use shared_db::{model::CreatePlayerModel, repo::player_repo::PlayerError};
+
+#[post("/register", data = "<body>")]
+pub async fn register_handler(
+    body: Json<CredentialsSchema>,
+    data: &State<AppRepositories>,
+) -> Result<Json<SessionResponse>, Custom<Json<GenericResponse>>> {
+    let register_request = body.into_inner();
+
+    let player_repo = &data.player_repo;
+    let player_create = CreatePlayerModel {
+        username: register_request.username,
+        password: register_request.password,
+    };
+    let created = match player_repo.create(player_create).await {
+        Ok(player) => player,
+        Err(
+            e @ (PlayerError::UsernameTaken
+            | PlayerError::InvalidUsername
+            | PlayerError::InvalidPassword),
+        ) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to register player: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    // log the new player in right away
+    let session_repo = &data.session_repo;
+    let (token, _) = match session_repo.create(created.id).await {
+        Ok(session) => session,
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to create session: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let json_response = SessionResponse {
+        status: "success".to_string(),
+        token,
+        data: created,
+    };
+    Ok(Json(json_response))
+}
+
+#[post("/login", data = "<body>")]
+pub async fn login_handler(
+    body: Json<CredentialsSchema>,
+    data: &State<AppRepositories>,
+) -> Result<Json<SessionResponse>, Custom<Json<GenericResponse>>> {
+    let login_request = body.into_inner();
+
+    let player_repo = &data.player_repo;
+    let player = match player_repo
+        .verify_credentials(&login_request.username, &login_request.password)
+        .await
+    {
+        Ok(player) => player,
+        Err(PlayerError::InvalidCredentials) => {
+            let error_response = Custom(
+                Status::Unauthorized,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Invalid username or password".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let session_repo = &data.session_repo;
+    let (token, _) = match session_repo.create(player.id).await {
+        Ok(session) => session,
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to create session: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let json_response = SessionResponse {
+        status: "success".to_string(),
+        token,
+        data: player,
+    };
+    Ok(Json(json_response))
+}
+
+#[post("/logout")]
+pub async fn logout_handler(
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<GenericResponse>, Status> {
+    let session_repo = &data.session_repo;
+
+    session_repo
+        .delete(&player.token)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let json_response = GenericResponse {
+        status: "success".to_string(),
+        message: "logged out".to_string(),
+    };
+    Ok(Json(json_response))
+}
+
+/// Issues a single-use ticket for opening the game state websocket, which
+/// expects `?ticket=<ticket>` rather than the session token.
+#[post("/socket-ticket")]
+pub async fn socket_ticket_handler(
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<SocketTicketResponse>, Status> {
+    let session_repo = &data.session_repo;
+
+    let ticket = session_repo
+        .create_socket_ticket(player.id)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let json_response = SocketTicketResponse {
+        status: "success".to_string(),
+        ticket,
+    };
+    Ok(Json(json_response))
+}
+
+#[get("/players/me")]
+pub async fn current_player_handler(
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<SinglePlayerResponse>, Status> {
+    let player_repo = &data.player_repo;
+
+    let retrieved = player_repo
+        .get_by_id(player.id)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let json_response = SinglePlayerResponse {
+        status: "success".to_string(),
+        data: retrieved,
+    };
+    Ok(Json(json_response))
+}
+
+#[catch(401)]
+pub fn unauthorized_catcher(_req: &Request) -> Json<GenericResponse> {
+    Json(GenericResponse {
+        status: "error".to_string(),
+        message: "Authentication required, provide a valid bearer token".to_string(),
+    })
+}
@@ -0,0 +1,107 @@
+use crate::{
+    guard::CurrentPlayer,
+    schema::{GenericResponse, SingleTechnologyResponse, TechnologyListResponse},
+    AppRepositories,
+};
+use rocket::{get, http::Status, post, response::status::Custom, serde::json::Json, State};
+use shared_db::service::purchase_service::PurchaseError;
+use uuid::Uuid;
+
+#[get("/research")]
+pub async fn technologies_list_handler(
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<TechnologyListResponse>, Status> {
+    let research_repo = &data.research_repo;
+
+    let technologies = research_repo
+        .get_all(player.id)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+    let effects = research_repo
+        .get_effects(player.id)
+        .await
+        .map_err(|_| Status::InternalServerError)?;
+
+    let json_response = TechnologyListResponse {
+        status: "success".to_string(),
+        results: technologies.len(),
+        effects,
+        technologies,
+    };
+    Ok(Json(json_response))
+}
+
+#[post("/research/<id>")]
+pub async fn purchase_research_handler(
+    id: String,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<SingleTechnologyResponse>, Custom<Json<GenericResponse>>> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
+        Err(_) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Invalid ID provided".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    // research technology and debit its cost atomically
+    let purchase_service = &data.purchase_service;
+    let researched = match purchase_service.purchase_research(player.id, uuid).await {
+        Ok(technology) => technology,
+        Err(PurchaseError::NotFound) => {
+            let error_response = Custom(
+                Status::NotFound,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Technology not found".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(PurchaseError::AlreadyPurchased) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "You have already researched this technology".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e @ (PurchaseError::InsufficientFunds | PurchaseError::MissingPrerequisites)) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to research technology: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let json_response = SingleTechnologyResponse {
+        status: "success".to_string(),
+        data: researched,
+    };
+    Ok(Json(json_response))
+}
@@ -0,0 +1,82 @@
+use crate::{
+    guard::CurrentPlayer,
+    helper::stats_range,
+    schema::{GenericResponse, StatsQuerySchema, StatsResponse},
+    AppRepositories,
+};
+
+use shared_db::{config::game_config, repo::transactions_repo::MoneyTransactionError};
+
+use rocket::{get, http::Status, response::status::Custom, serde::json::Json, State};
+
+#[get("/stats?<query..>")]
+pub async fn stats_handler(
+    query: StatsQuerySchema,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<StatsResponse>, Custom<Json<GenericResponse>>> {
+    let (bucket_secs, from, to) = match stats_range(&query) {
+        Ok(res) => res,
+        Err(message) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message,
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let finance_repo = &data.finances_repo;
+    let cashflow = match finance_repo
+        .get_cashflow(player.id, bucket_secs, from, to)
+        .await
+    {
+        Ok(res) => res,
+        Err(MoneyTransactionError::InvalidFilter) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: "Invalid time range provided".to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to load statistics: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let platforms = match finance_repo.get_platform_returns(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to load statistics: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let json_response = StatsResponse {
+        status: "success".to_string(),
+        bucket_secs,
+        tick_period_secs: game_config().tick_period_secs,
+        cashflow,
+        platforms,
+    };
+    Ok(Json(json_response))
+}
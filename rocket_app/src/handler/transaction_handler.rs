@@ -0,0 +1,67 @@
+use crate::{
+    guard::CurrentPlayer,
+    helper::transaction_filter,
+    schema::{GenericResponse, TransactionListResponse, TransactionQuerySchema},
+    AppRepositories,
+};
+
+use shared_db::repo::transactions_repo::MoneyTransactionError;
+
+use rocket::{get, http::Status, response::status::Custom, serde::json::Json, State};
+
+#[get("/transactions?<query..>")]
+pub async fn transactions_list_handler(
+    query: TransactionQuerySchema,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Json<TransactionListResponse>, Custom<Json<GenericResponse>>> {
+    let filter = match transaction_filter(query) {
+        Ok(res) => res,
+        Err(message) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message,
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+    let (page, limit) = (filter.page, filter.limit);
+
+    let finance_repo = &data.finances_repo;
+    let history = match finance_repo.get_history(player.id, filter).await {
+        Ok(res) => res,
+        Err(e @ MoneyTransactionError::InvalidFilter) => {
+            let error_response = Custom(
+                Status::BadRequest,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: e.to_string(),
+                }),
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Custom(
+                Status::InternalServerError,
+                Json(GenericResponse {
+                    status: "error".to_string(),
+                    message: format!("Failed to load transactions: {}", e),
+                }),
+            );
+            return Err(error_response);
+        }
+    };
+
+    let json_response = TransactionListResponse {
+        status: "success".to_string(),
+        results: history.entries.len(),
+        total: history.total,
+        page,
+        limit,
+        transactions: history.entries,
+    };
+    Ok(Json(json_response))
+}
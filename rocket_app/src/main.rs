@@ -1,3 +1,4 @@
+mod guard;
 mod handler;
 mod helper;
 mod schema;
@@ -5,9 +6,16 @@ mod ui_handler;
 
 use dotenv::dotenv;
 
-use shared_db::repo::{
-    beer_repo::BeerRepo, generic::Repo, platform_repo::OilPlaftormRepo,
-    transactions_repo::TransactionsRepo,
+use shared_db::{
+    config::load_game_config,
+    repo::{
+        achievement_repo::AchievementRepo, beer_repo::BeerRepo, event_repo::MarketEventRepo,
+        generic::Repo, leaderboard_repo::LeaderboardRepo, oil_price_repo::OilPriceRepo,
+        platform_repo::OilPlaftormRepo, platform_type_repo::PlatformTypeRepo,
+        player_repo::PlayerRepo, research_repo::ResearchRepo, session_repo::SessionRepo,
+        transactions_repo::TransactionsRepo,
+    },
+    service::{achievement_service::AchievementService, purchase_service::PurchaseService},
 };
 
 use helper::seed_game_entities;
@@ -22,42 +30,103 @@ use rocket::http::Method;
 use rocket_cors::{AllowedOrigins, CorsOptions};
 
 use handler::{
+    achievement_handler::achievements_list_handler,
     beer_handler::{beers_list_handler, purchase_beer_handler},
+    event_handler::market_events_list_handler,
+    leaderboard_handler::leaderboard_handler,
+    oil_price_handler::oil_prices_list_handler,
     platform_handler::{
-        create_platform_handler, edit_platform_handler, health_checker_handler,
-        platforms_list_handler,
+        bulk_create_platforms_handler, create_platform_handler, edit_platform_handler,
+        health_checker_handler, platforms_list_handler, repair_platform_handler,
+        sell_platform_handler, upgrade_platform_levels_handler,
+    },
+    platform_type_handler::{
+        create_platform_type_handler, delete_platform_type_handler, edit_platform_type_handler,
+        forbidden_catcher, platform_types_list_handler,
     },
+    player_handler::{
+        current_player_handler, login_handler, logout_handler, register_handler,
+        socket_ticket_handler, unauthorized_catcher,
+    },
+    research_handler::{purchase_research_handler, technologies_list_handler},
+    stats_handler::stats_handler,
+    transaction_handler::transactions_list_handler,
 };
 
 use ui_handler::{
     beer_handler::purchase_beer_ui_handler,
     common_handler::{game_won_handler, index_handler},
+    leaderboard_handler::leaderboard_ui_handler,
     platform_handler::{
-        create_platform_ui_handler, get_create_platform_ui_handler, upgrade_platform_ui_handler,
+        create_platform_ui_handler, get_create_platform_ui_handler, repair_platform_ui_handler,
+        sell_platform_ui_handler, upgrade_platform_levels_ui_handler, upgrade_platform_ui_handler,
+    },
+    player_handler::{
+        get_login_ui_handler, get_register_ui_handler, login_ui_handler, logout_ui_handler,
+        register_ui_handler, unauthorized_ui_catcher,
     },
+    research_handler::{purchase_research_ui_handler, research_ui_handler},
+    stats_handler::stats_ui_handler,
+    transaction_handler::history_handler,
 };
 
 struct AppRepositories {
+    player_repo: PlayerRepo,
+    session_repo: SessionRepo,
     platform_repo: OilPlaftormRepo,
+    platform_type_repo: PlatformTypeRepo,
     beer_repo: BeerRepo,
     finances_repo: TransactionsRepo,
+    event_repo: MarketEventRepo,
+    oil_price_repo: OilPriceRepo,
+    research_repo: ResearchRepo,
+    achievement_repo: AchievementRepo,
+    leaderboard_repo: LeaderboardRepo,
+    purchase_service: PurchaseService,
+    achievement_service: AchievementService,
 }
 
 async fn initialize_repositories(pool: &PgPool) -> AppRepositories {
+    let player_repo = PlayerRepo::new(pool.clone());
+    let session_repo = SessionRepo::new(pool.clone());
     let platform_repo = OilPlaftormRepo::new(pool.clone());
+    let platform_type_repo = PlatformTypeRepo::new(pool.clone());
     let beer_repo = BeerRepo::new(pool.clone());
     let finances_repo = TransactionsRepo::new(pool.clone());
+    let event_repo = MarketEventRepo::new(pool.clone());
+    let oil_price_repo = OilPriceRepo::new(pool.clone());
+    let research_repo = ResearchRepo::new(pool.clone());
+    let achievement_repo = AchievementRepo::new(pool.clone());
+    let leaderboard_repo = LeaderboardRepo::new(pool.clone());
+    let purchase_service = PurchaseService::new(pool.clone());
+    let achievement_service = AchievementService::new(pool.clone());
     AppRepositories {
+        player_repo,
+        session_repo,
         platform_repo,
+        platform_type_repo,
         beer_repo,
         finances_repo,
+        event_repo,
+        oil_price_repo,
+        research_repo,
+        achievement_repo,
+        leaderboard_repo,
+        purchase_service,
+        achievement_service,
     }
 }
 
 #[rocket::main]
-async fn main() -> Result<(), rocket::Error> {
+async fn main() {
     dotenv().ok();
 
+    // load game balancing
+    if let Err(err) = load_game_config() {
+        println!("{}", err);
+        std::process::exit(1);
+    }
+
     // setup database connection
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let pool = match PgPoolOptions::new()
@@ -93,7 +162,7 @@ async fn main() -> Result<(), rocket::Error> {
     let cors = CorsOptions::default()
         .allowed_origins(AllowedOrigins::all())
         .allowed_methods(
-            vec![Method::Get, Method::Post, Method::Patch]
+            vec![Method::Get, Method::Post, Method::Patch, Method::Delete]
                 .into_iter()
                 .map(From::from)
                 .collect(),
@@ -107,11 +176,32 @@ async fn main() -> Result<(), rocket::Error> {
             "/api",
             routes![
                 health_checker_handler,
+                register_handler,
+                login_handler,
+                logout_handler,
+                socket_ticket_handler,
+                current_player_handler,
                 platforms_list_handler,
                 create_platform_handler,
+                bulk_create_platforms_handler,
                 edit_platform_handler,
+                upgrade_platform_levels_handler,
+                repair_platform_handler,
+                sell_platform_handler,
+                platform_types_list_handler,
+                create_platform_type_handler,
+                edit_platform_type_handler,
+                delete_platform_type_handler,
                 beers_list_handler,
                 purchase_beer_handler,
+                market_events_list_handler,
+                oil_prices_list_handler,
+                technologies_list_handler,
+                purchase_research_handler,
+                achievements_list_handler,
+                leaderboard_handler,
+                transactions_list_handler,
+                stats_handler,
             ],
         )
         .mount(
@@ -121,15 +211,28 @@ async fn main() -> Result<(), rocket::Error> {
                 get_create_platform_ui_handler,
                 create_platform_ui_handler,
                 upgrade_platform_ui_handler,
+                upgrade_platform_levels_ui_handler,
+                repair_platform_ui_handler,
+                sell_platform_ui_handler,
                 purchase_beer_ui_handler,
+                research_ui_handler,
+                purchase_research_ui_handler,
                 game_won_handler,
+                history_handler,
+                stats_ui_handler,
+                leaderboard_ui_handler,
+                get_login_ui_handler,
+                login_ui_handler,
+                get_register_ui_handler,
+                register_ui_handler,
+                logout_ui_handler,
             ],
         )
+        .register("/api", catchers![unauthorized_catcher, forbidden_catcher])
+        .register("/", catchers![unauthorized_ui_catcher])
         .attach(cors.to_cors().unwrap())
         .attach(Template::fairing())
         .launch()
         .await
         .expect("Failed to start server");
-
-    Ok(())
 }
@@ -1,41 +1,149 @@
 use serde::{Deserialize, Serialize};
-use shared_db::model::{BeerModel, OilPlatformModel};
+use shared_db::model::{
+    AchievementModel, BeerModel, CashflowBucketModel, LeaderboardEntryModel, LeaderboardOrder,
+    LedgerEntryModel, MarketEventModel, Money, OilPlatformModel, OilPriceModel,
+    PlatformOverviewModel, PlatformReturnModel, PlatformTypeModel, PlayerModel,
+    ResearchEffectsModel, TechnologyModel,
+};
 
 // requests
-#[derive(Deserialize, Debug, Clone)]
-pub struct ParamOptions {
-    pub id: String,
+#[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
+pub struct CreatePlatformSchema {
+    pub platform_type: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
-pub struct CreatePlatformSchema {
+pub struct BulkPurchaseSchema {
     pub platform_type: String,
+    pub count: i64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct CreatePlatformUpgradeSchema;
+// without `levels` the platform is upgraded to its maximum level
+#[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
+pub struct BulkUpgradeSchema {
+    pub levels: Option<i64>,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct CreateMoneyTransactionSchema {
-    pub item_id: String,
-    pub amount: i64,
+pub struct CreatePlatformTypeSchema {
+    pub name: String,
+    pub cost: i64,
+    pub upgrade_cost: i64,
+    pub profitability: i64,
+    pub max_level: i16,
+    pub image: String,
+    // running the type costs nothing if left out
+    #[serde(default)]
+    pub upkeep: i64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct PurchaseBeerSchema;
+pub struct UpdatePlatformTypeSchema {
+    pub name: Option<String>,
+    pub cost: Option<i64>,
+    pub upgrade_cost: Option<i64>,
+    pub profitability: Option<i64>,
+    pub max_level: Option<i16>,
+    pub image: Option<String>,
+    pub upkeep: Option<i64>,
+}
+
+// query of the transaction history, times are unix timestamps
+#[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
+pub struct TransactionQuerySchema {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub reduces_balance: Option<bool>,
+    pub item_id: Option<String>,
+}
+
+// bucket is `minute` or `hour`, times are unix timestamps
+#[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
+pub struct StatsQuerySchema {
+    pub bucket: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+// defaults to the last hour
+#[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
+pub struct OilPriceQuerySchema {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+// sort is `time` or `efficiency`
+#[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
+pub struct LeaderboardQuerySchema {
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, FromForm)]
+pub struct CredentialsSchema {
+    pub username: String,
+    pub password: String,
+}
 
 // responses
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SinglePlatformResponse {
     pub status: String,
+    pub data: PlatformOverviewModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlatformSaleResponse {
+    pub status: String,
+    pub refund: Money,
     pub data: OilPlatformModel,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlatformRepairResponse {
+    pub status: String,
+    pub cost: Money,
+    pub data: PlatformOverviewModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkPurchaseResponse {
+    pub status: String,
+    pub requested: i64,
+    pub purchased: usize,
+    pub total_cost: Money,
+    pub platforms: Vec<PlatformOverviewModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkUpgradeResponse {
+    pub status: String,
+    pub requested: i64,
+    pub upgraded: i64,
+    pub total_cost: Money,
+    pub data: PlatformOverviewModel,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PlatformListResponse {
     pub status: String,
     pub results: usize,
-    pub platforms: Vec<OilPlatformModel>,
+    pub platforms: Vec<PlatformOverviewModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SinglePlatformTypeResponse {
+    pub status: String,
+    pub data: PlatformTypeModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlatformTypeListResponse {
+    pub status: String,
+    pub results: usize,
+    pub platform_types: Vec<PlatformTypeModel>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -51,6 +159,89 @@ pub struct BeerListResponse {
     pub beers: Vec<BeerModel>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MarketEventListResponse {
+    pub status: String,
+    pub results: usize,
+    pub events: Vec<MarketEventModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OilPriceHistoryResponse {
+    pub status: String,
+    pub results: usize,
+    pub current_price: f64,
+    pub prices: Vec<OilPriceModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SingleTechnologyResponse {
+    pub status: String,
+    pub data: TechnologyModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TechnologyListResponse {
+    pub status: String,
+    pub results: usize,
+    pub effects: ResearchEffectsModel,
+    pub technologies: Vec<TechnologyModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AchievementListResponse {
+    pub status: String,
+    pub results: usize,
+    pub unlocked: usize,
+    pub achievements: Vec<AchievementModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeaderboardResponse {
+    pub status: String,
+    pub results: usize,
+    pub sort: LeaderboardOrder,
+    pub entries: Vec<LeaderboardEntryModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionListResponse {
+    pub status: String,
+    pub results: usize,
+    pub total: i64,
+    pub page: i64,
+    pub limit: i64,
+    pub transactions: Vec<LedgerEntryModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StatsResponse {
+    pub status: String,
+    pub bucket_secs: i64,
+    pub tick_period_secs: i64,
+    pub cashflow: Vec<CashflowBucketModel>,
+    pub platforms: Vec<PlatformReturnModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SinglePlayerResponse {
+    pub status: String,
+    pub data: PlayerModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionResponse {
+    pub status: String,
+    pub token: String,
+    pub data: PlayerModel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SocketTicketResponse {
+    pub status: String,
+    pub ticket: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GenericResponse {
     pub status: String,
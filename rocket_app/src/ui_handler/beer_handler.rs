@@ -1,12 +1,13 @@
-use crate::AppRepositories;
+use crate::{guard::CurrentPlayer, AppRepositories};
 use rocket::{response::Redirect, State};
 use rocket_dyn_templates::{context, Template};
-use shared_db::{model::CreateMoneyTransactionModel, repo::beer_repo::BeerError};
+use shared_db::service::purchase_service::PurchaseError;
 use uuid::Uuid;
 
 #[post("/beers/<id>")]
 pub async fn purchase_beer_ui_handler(
     id: String,
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
 ) -> Result<Redirect, Template> {
     // validate provided id
@@ -23,49 +24,20 @@ pub async fn purchase_beer_ui_handler(
         }
     };
 
-    // check if exists
-    let beer_repo = &data.beer_repo;
-    let retrieved = match beer_repo.get_by_id(uuid).await {
-        Ok(beer) => beer,
-        Err(e) => {
-            let error_response = Template::render(
-                "error/500",
-                context! {
-                    error: e.to_string(),
-                },
-            );
-            return Err(error_response);
-        }
-    };
-
-    // validate amount of available money
-    let finances_repo = &data.finances_repo;
-    let balance = match finances_repo.get_available_balance().await {
-        Ok(bal) => bal,
-        Err(_) => {
+    // purchase beer and debit its cost atomically
+    let purchase_service = &data.purchase_service;
+    match purchase_service.purchase_beer(player.id, uuid).await {
+        Ok(_) => (),
+        Err(PurchaseError::InsufficientFunds) => {
             let error_response = Template::render(
-                "error/500",
+                "error/400",
                 context! {
-                    error: "Failed to upgrade beer".to_string(),
+                    error: "Not enough funds for purchase".to_string(),
                 },
             );
             return Err(error_response);
         }
-    };
-    if retrieved.cost > balance {
-        let error_response = Template::render(
-            "error/400",
-            context! {
-                error: "Not enough funds for purchase".to_string(),
-            },
-        );
-        return Err(error_response);
-    }
-
-    // update and save
-    let updated = match beer_repo.purchase(uuid).await {
-        Ok(platform) => platform,
-        Err(BeerError::AlreadyPurchased) => {
+        Err(PurchaseError::AlreadyPurchased) => {
             let error_response = Template::render(
                 "error/400",
                 context! {
@@ -85,27 +57,9 @@ pub async fn purchase_beer_ui_handler(
         }
     };
 
-    // create associated money transaction
-    let platform_update_tx = CreateMoneyTransactionModel {
-        item_id: Some(updated.id),
-        amount: updated.cost,
-        reduces_balance: true,
-    };
-    match finances_repo.create(platform_update_tx).await {
-        Ok(_) => (),
-        Err(e) => {
-            let error_response = Template::render(
-                "error/500",
-                context! {
-                    error: format!("Failed to create platform: {}", e.to_string()),
-                },
-            );
-            return Err(error_response);
-        }
-    };
-
     // check if all beers purchased
-    let all_beers = match beer_repo.get_all().await {
+    let beer_repo = &data.beer_repo;
+    let all_beers = match beer_repo.get_all(player.id).await {
         Ok(res) => res,
 
         Err(e) => {
@@ -1,14 +1,95 @@
-use crate::AppRepositories;
-use rocket::{get, State};
+use std::collections::HashMap;
+
+use crate::{
+    guard::{CurrentPlayer, SocketTicket},
+    AppRepositories,
+};
+use rocket::{get, request::FlashMessage, State};
 use rocket_dyn_templates::{context, Template};
-use shared_db::repo::generic::Repo;
+use shared_db::{
+    config::game_config,
+    model::{LeaderboardOrder, Money},
+    repo::{generic::Repo, transactions_repo::MoneyTransactionError},
+};
 
 #[get("/")]
-pub async fn index_handler(data: &State<AppRepositories>) -> Template {
+pub async fn index_handler(
+    player: CurrentPlayer,
+    socket_ticket: SocketTicket,
+    flash: Option<FlashMessage<'_>>,
+    data: &State<AppRepositories>,
+) -> Template {
     let beers_repo = &data.beer_repo;
     let oil_platform_repo = &data.platform_repo;
+    let platform_type_repo = &data.platform_type_repo;
+    let finance_repo = &data.finances_repo;
+    let event_repo = &data.event_repo;
+    let oil_price_repo = &data.oil_price_repo;
+    let research_repo = &data.research_repo;
+
+    // credit whatever was earned while the player was away
+    let settlement = match finance_repo.settle_offline(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    let beers = match beers_repo.get_all(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+    let platforms = match oil_platform_repo.get_overviews(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    let platform_types = match platform_type_repo.get_all().await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let market_events = match event_repo.get_active(now).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
 
-    let beers = match beers_repo.get_all().await {
+    // the last hour for the ticker, live prices are appended by the script
+    let oil_prices = match oil_price_repo.get_history(now - 3600, now).await {
         Ok(res) => res,
         Err(e) => {
             return Template::render(
@@ -19,7 +100,8 @@ pub async fn index_handler(data: &State<AppRepositories>) -> Template {
             );
         }
     };
-    let platforms = match oil_platform_repo.get_all().await {
+
+    let effects = match research_repo.get_effects(player.id).await {
         Ok(res) => res,
         Err(e) => {
             return Template::render(
@@ -31,21 +113,53 @@ pub async fn index_handler(data: &State<AppRepositories>) -> Template {
         }
     };
 
+    // upgrade limits and repair costs for updating the cards live
+    let max_levels: HashMap<String, i16> = platform_types
+        .iter()
+        .map(|platform_type| {
+            let max_level = effects.max_level(platform_type.max_level);
+            (platform_type.name.clone(), max_level)
+        })
+        .collect();
+    let platform_costs: HashMap<String, i64> = platform_types
+        .iter()
+        .map(|platform_type| (platform_type.name.clone(), platform_type.cost))
+        .collect();
+
     Template::render(
         "index",
         context! {
             platforms: platforms,
             beers: beers,
+            earned_while_away: settlement.earned,
+            notice: flash.map(|flash| flash.message().to_string()),
+            platform_types: platform_types,
+            market_events: market_events,
+            oil_prices: oil_prices,
+            base_oil_price: game_config().oil_market.base_price,
+            max_levels: max_levels,
+            max_level_bonus: effects.max_level_bonus,
+            profitability_multiplier: effects.profitability_multiplier,
+            platform_costs: platform_costs,
+            repair_cost_fraction: game_config().maintenance.repair_cost_fraction,
+            tick_period: game_config().tick_period_secs,
+            sell_refund_percent: (game_config().sell_refund_fraction * 100.0).round() as i64,
+            player: player.username,
+            socket_ticket: socket_ticket.0,
         },
     )
 }
 
 #[get("/win")]
-pub async fn game_won_handler(data: &State<AppRepositories>) -> Template {
+pub async fn game_won_handler(player: CurrentPlayer, data: &State<AppRepositories>) -> Template {
     let platform_repo = &data.platform_repo;
+    let platform_type_repo = &data.platform_type_repo;
     let finance_repo = &data.finances_repo;
+    let achievement_repo = &data.achievement_repo;
+    let achievement_service = &data.achievement_service;
+    let leaderboard_repo = &data.leaderboard_repo;
 
-    let platforms = match platform_repo.get_all().await {
+    let platforms = match platform_repo.get_all(player.id).await {
         Ok(res) => res,
         Err(e) => {
             return Template::render(
@@ -56,7 +170,7 @@ pub async fn game_won_handler(data: &State<AppRepositories>) -> Template {
             );
         }
     };
-    let txs = match finance_repo.get_all().await {
+    let txs = match finance_repo.get_all(player.id).await {
         Ok(res) => res,
         Err(e) => {
             return Template::render(
@@ -69,16 +183,92 @@ pub async fn game_won_handler(data: &State<AppRepositories>) -> Template {
     };
 
     // prepare game summary
-    let total_earned = txs
-        .iter()
-        .filter(|tx| !tx.reduces_balance)
-        .map(|tx| tx.amount)
-        .sum::<i64>();
-    let total_spent = txs
-        .iter()
-        .filter(|tx| tx.reduces_balance)
-        .map(|tx| tx.amount)
-        .sum::<i64>();
+    let total_earned = Money::checked_sum(
+        txs.iter()
+            .filter(|tx| !tx.reduces_balance)
+            .map(|tx| tx.amount),
+    );
+    let total_spent = Money::checked_sum(
+        txs.iter()
+            .filter(|tx| tx.reduces_balance)
+            .map(|tx| tx.amount),
+    );
+    let (total_earned, total_spent) = match (total_earned, total_spent) {
+        (Some(earned), Some(spent)) => (earned, spent),
+        _ => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: MoneyTransactionError::Overflow.to_string(),
+                },
+            );
+        }
+    };
+
+    let platform_types = match platform_type_repo.get_all().await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    // unlock the achievement for winning right away, even if the websocket
+    // app did not catch the last purchase
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = achievement_service.evaluate(player.id, now).await {
+        return Template::render(
+            "error/500",
+            context! {
+                error: e.to_string(),
+            },
+        );
+    }
+    let achievements = match achievement_repo.get_all(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    // the game recorded when the last beer was bought
+    let time_entry = match leaderboard_repo
+        .get_entry(player.id, LeaderboardOrder::Time)
+        .await
+    {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+    let efficiency_entry = match leaderboard_repo
+        .get_entry(player.id, LeaderboardOrder::Efficiency)
+        .await
+    {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
 
     Template::render(
         "game_over",
@@ -86,6 +276,12 @@ pub async fn game_won_handler(data: &State<AppRepositories>) -> Template {
             platforms: platforms,
             earned: total_earned,
             spent: total_spent,
+            platform_types: platform_types,
+            achievements: achievements,
+            time_entry: time_entry,
+            efficiency_entry: efficiency_entry,
+            tick_period: game_config().tick_period_secs,
+            player: player.username,
         },
     )
 }
@@ -0,0 +1,77 @@
+use crate::{
+    guard::{CurrentPlayer, SocketTicket},
+    helper::leaderboard_order,
+    schema::LeaderboardQuerySchema,
+    AppRepositories,
+};
+use rocket::{get, State};
+use rocket_dyn_templates::{context, Template};
+use shared_db::{constants::LEADERBOARD_SIZE, repo::leaderboard_repo::LeaderboardError};
+
+#[get("/leaderboard?<query..>")]
+pub async fn leaderboard_ui_handler(
+    query: LeaderboardQuerySchema,
+    player: CurrentPlayer,
+    socket_ticket: SocketTicket,
+    data: &State<AppRepositories>,
+) -> Template {
+    let order = match leaderboard_order(&query) {
+        Ok(res) => res,
+        Err(error) => {
+            return Template::render(
+                "error/400",
+                context! {
+                    error: error,
+                },
+            );
+        }
+    };
+
+    let leaderboard_repo = &data.leaderboard_repo;
+    let entries = match leaderboard_repo
+        .get_ranking(order, query.limit.unwrap_or(LEADERBOARD_SIZE))
+        .await
+    {
+        Ok(res) => res,
+        Err(e @ LeaderboardError::InvalidLimit) => {
+            return Template::render(
+                "error/400",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    // the player's own game, even if it did not make the top
+    let own_entry = match leaderboard_repo.get_entry(player.id, order).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    Template::render(
+        "leaderboard",
+        context! {
+            entries: entries,
+            own_entry: own_entry,
+            sort: order,
+            player: player.username,
+            socket_ticket: socket_ticket.0,
+        },
+    )
+}
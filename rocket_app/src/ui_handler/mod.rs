@@ -1,3 +1,8 @@
 pub mod beer_handler;
 pub mod common_handler;
+pub mod leaderboard_handler;
 pub mod platform_handler;
+pub mod player_handler;
+pub mod research_handler;
+pub mod stats_handler;
+pub mod transaction_handler;
@@ -1,27 +1,25 @@
-use std::panic;
-
 use rocket::form::{Form, Strict};
-use rocket::response::Redirect;
+use rocket::response::{Flash, Redirect};
 use rocket_dyn_templates::{context, Template};
 
 use crate::{
-    helper::{get_platform_cost, get_platform_profitability, get_platform_upgrade_cost},
-    schema::CreatePlatformSchema,
+    guard::CurrentPlayer,
+    schema::{BulkPurchaseSchema, BulkUpgradeSchema},
     AppRepositories,
 };
-use shared_db::{
-    model::{CreateMoneyTransactionModel, CreatePlatformModel, UpdatePlatformModel},
-    repo::{generic::Repo, platform_repo::OilPlatformError},
-};
+use shared_db::{constants::MAX_BULK_PURCHASE, service::purchase_service::PurchaseError};
 
 use rocket::{get, post, State};
 use uuid::Uuid;
 
 #[get("/platforms/create")]
-pub async fn get_create_platform_ui_handler(data: &State<AppRepositories>) -> Template {
+pub async fn get_create_platform_ui_handler(
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Template {
     // get money balance info
     let finances_repo = &data.finances_repo;
-    let balance = match finances_repo.get_available_balance().await {
+    let balance = match finances_repo.get_available_balance(player.id).await {
         Ok(bal) => bal,
         Err(_) => {
             return Template::render(
@@ -33,110 +31,180 @@ pub async fn get_create_platform_ui_handler(data: &State<AppRepositories>) -> Te
         }
     };
 
+    // get purchasable platform types
+    let platform_type_repo = &data.platform_type_repo;
+    let platform_types = match platform_type_repo.get_all().await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    // types that need research first
+    let research_repo = &data.research_repo;
+    let locked_types = match research_repo.get_locked_platform_types(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
     Template::render(
         "create_platform",
         context! {
             balance: balance,
+            platform_types: platform_types,
+            locked_types: locked_types,
+            max_bulk_purchase: MAX_BULK_PURCHASE,
+            player: player.username,
         },
     )
 }
 
 #[post("/platforms/create", data = "<body>")]
 pub async fn create_platform_ui_handler(
-    body: Form<Strict<CreatePlatformSchema>>,
+    body: Form<Strict<BulkPurchaseSchema>>,
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
-) -> Result<Redirect, Template> {
+) -> Result<Flash<Redirect>, Template> {
     let create_request = body.into_inner().into_inner();
 
-    // validate platform type
-    let platform_type_ref: &str = &create_request.platform_type;
-    let result = panic::catch_unwind(|| platform_type_ref.into());
-
-    let validated_platform_type = match result {
-        Ok(validated) => validated,
-        Err(e) => {
-            println!("Panic occurred: {:?}", e);
+    // purchase as many platforms as affordable and debit them atomically
+    let purchase_service = &data.purchase_service;
+    let purchase = match purchase_service
+        .purchase_platforms(
+            player.id,
+            &create_request.platform_type,
+            create_request.count,
+        )
+        .await
+    {
+        Ok(purchase) => purchase,
+        Err(
+            e @ (PurchaseError::UnknownPlatformType
+            | PurchaseError::NotResearched
+            | PurchaseError::InvalidQuantity),
+        ) => {
             let error_response = Template::render(
-                "error/500",
+                "error/400",
                 context! {
-                    error: "Invalid platform type specified".to_string(),
+                    error: e.to_string(),
                 },
             );
             return Err(error_response);
         }
-    };
-
-    // validate amount of available money
-    let finances_repo = &data.finances_repo;
-    let balance = match finances_repo.get_available_balance().await {
-        Ok(bal) => bal,
-        Err(_) => {
+        Err(PurchaseError::InsufficientFunds) => {
             let error_response = Template::render(
-                "error/500",
+                "error/400",
                 context! {
-                    error: "Failed to create platform".to_string(),
+                    error: "Not enough funds for purchase".to_string(),
                 },
             );
             return Err(error_response);
         }
-    };
-    let cost = get_platform_cost(validated_platform_type);
-    if cost > balance {
-        let error_response = Template::render(
-            "error/400",
-            context! {
-                error: "Not enough funds for purchase".to_string(),
-            },
-        );
-        return Err(error_response);
-    }
-
-    // save to database
-    let platform_create = CreatePlatformModel {
-        platform_type: validated_platform_type,
-        profitability: get_platform_profitability(validated_platform_type),
-    };
-    let oil_platform_repo = &data.platform_repo;
-    let created = match oil_platform_repo.create(platform_create).await {
-        Ok(platform) => platform,
         Err(e) => {
             let error_response = Template::render(
                 "error/500",
                 context! {
-                    error: format!("Failed to create platform: {}", e.to_string()),
+                    error: format!("Failed to create platform: {}", e),
                 },
             );
             return Err(error_response);
         }
     };
 
-    // create associated money transaction
-    let platform_create_tx = CreateMoneyTransactionModel {
-        item_id: Some(created.id),
-        amount: cost,
-        reduces_balance: true,
+    let message = format!(
+        "Purchased {} of {} {} platforms for $ {}",
+        purchase.platforms.len(),
+        purchase.requested,
+        create_request.platform_type,
+        purchase.total_cost
+    );
+    Ok(Flash::success(Redirect::to(uri!("/")), message))
+}
+
+#[post("/platforms/edit/<id>")]
+pub async fn upgrade_platform_ui_handler(
+    id: String,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Redirect, Template> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
+        Err(_) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "Invalid ID provided".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
     };
-    match finances_repo.create(platform_create_tx).await {
+
+    // upgrade platform and debit its cost atomically
+    let purchase_service = &data.purchase_service;
+    match purchase_service.upgrade_platform(player.id, uuid).await {
         Ok(_) => (),
+        Err(e @ PurchaseError::NotFound) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+        Err(PurchaseError::InsufficientFunds) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "Not enough funds for purchase".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+        Err(PurchaseError::MaxLevelReached) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "You have already upgraded the platform to the maximum".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
         Err(e) => {
             let error_response = Template::render(
                 "error/500",
                 context! {
-                    error: format!("Failed to create platform: {}", e.to_string()),
+                    error: e.to_string(),
                 },
             );
             return Err(error_response);
         }
     };
 
-    Ok(Redirect::to(uri!("/")))
+    Ok(Redirect::to("/"))
 }
 
-#[post("/platforms/edit/<id>")]
-pub async fn upgrade_platform_ui_handler(
+#[post("/platforms/edit/<id>/levels", data = "<body>")]
+pub async fn upgrade_platform_levels_ui_handler(
     id: String,
+    body: Form<BulkUpgradeSchema>,
+    player: CurrentPlayer,
     data: &State<AppRepositories>,
-) -> Result<Redirect, Template> {
+) -> Result<Flash<Redirect>, Template> {
     // validate provided id
     let uuid = match Uuid::parse_str(&id) {
         Ok(res) => res,
@@ -151,10 +219,35 @@ pub async fn upgrade_platform_ui_handler(
         }
     };
 
-    // check if exists
-    let oil_platform_repo = &data.platform_repo;
-    let retrieved = match oil_platform_repo.get_by_id(uuid).await {
-        Ok(platform) => platform,
+    // upgrade as many levels as affordable and debit them atomically
+    let purchase_service = &data.purchase_service;
+    let upgrade = match purchase_service
+        .upgrade_platform_levels(player.id, uuid, body.into_inner().levels)
+        .await
+    {
+        Ok(upgrade) => upgrade,
+        Err(
+            e @ (PurchaseError::NotFound
+            | PurchaseError::InvalidQuantity
+            | PurchaseError::InsufficientFunds),
+        ) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+        Err(PurchaseError::MaxLevelReached) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "You have already upgraded the platform to the maximum".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
         Err(e) => {
             let error_response = Template::render(
                 "error/500",
@@ -166,42 +259,46 @@ pub async fn upgrade_platform_ui_handler(
         }
     };
 
-    // validate amount of available money
-    let finances_repo = &data.finances_repo;
-    let balance = match finances_repo.get_available_balance().await {
-        Ok(bal) => bal,
+    let message = format!(
+        "Upgraded {} of {} levels for $ {}",
+        upgrade.levels, upgrade.requested, upgrade.total_cost
+    );
+    Ok(Flash::success(Redirect::to("/"), message))
+}
+
+#[post("/platforms/repair/<id>")]
+pub async fn repair_platform_ui_handler(
+    id: String,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Flash<Redirect>, Template> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
         Err(_) => {
             let error_response = Template::render(
-                "error/500",
+                "error/400",
                 context! {
-                    error: "Failed to upgrade platform".to_string(),
+                    error: "Invalid ID provided".to_string(),
                 },
             );
             return Err(error_response);
         }
     };
-    let cost = get_platform_upgrade_cost(retrieved.platform_type);
-    if cost > balance {
-        let error_response = Template::render(
-            "error/400",
-            context! {
-                error: "Not enough funds for purchase".to_string(),
-            },
-        );
-        return Err(error_response);
-    }
-
-    // update and save
-    let platform_update = UpdatePlatformModel {
-        profitability_addition: get_platform_profitability(retrieved.platform_type),
-    };
-    let updated = match oil_platform_repo.update(uuid, platform_update).await {
-        Ok(platform) => platform,
-        Err(OilPlatformError::MaxLevelReached) => {
+
+    // restore the condition and debit the repair atomically
+    let purchase_service = &data.purchase_service;
+    let repair = match purchase_service.repair_platform(player.id, uuid).await {
+        Ok(repair) => repair,
+        Err(
+            e @ (PurchaseError::NotFound
+            | PurchaseError::InsufficientFunds
+            | PurchaseError::NothingToRepair),
+        ) => {
             let error_response = Template::render(
                 "error/400",
                 context! {
-                    error: "You have already upgraded the platform to the maximum".to_string(),
+                    error: e.to_string(),
                 },
             );
             return Err(error_response);
@@ -210,26 +307,58 @@ pub async fn upgrade_platform_ui_handler(
             let error_response = Template::render(
                 "error/500",
                 context! {
-                    error: e.to_string(),
+                    error: format!("Failed to repair platform: {}", e),
                 },
             );
             return Err(error_response);
         }
     };
 
-    // create associated money transaction
-    let platform_update_tx = CreateMoneyTransactionModel {
-        item_id: Some(updated.id),
-        amount: cost,
-        reduces_balance: true,
+    let message = format!(
+        "Repaired the {} platform for $ {}",
+        repair.platform.platform_type, repair.cost
+    );
+    Ok(Flash::success(Redirect::to("/"), message))
+}
+
+#[post("/platforms/sell/<id>")]
+pub async fn sell_platform_ui_handler(
+    id: String,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Redirect, Template> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
+        Err(_) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "Invalid ID provided".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
     };
-    match finances_repo.create(platform_update_tx).await {
+
+    // demolish platform and credit the refund atomically
+    let purchase_service = &data.purchase_service;
+    match purchase_service.sell_platform(player.id, uuid).await {
         Ok(_) => (),
+        Err(e @ PurchaseError::NotFound) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+            return Err(error_response);
+        }
         Err(e) => {
             let error_response = Template::render(
                 "error/500",
                 context! {
-                    error: format!("Failed to create platform: {}", e.to_string()),
+                    error: format!("Failed to sell platform: {}", e),
                 },
             );
             return Err(error_response);
@@ -0,0 +1,159 @@
+use rocket::form::{Form, Strict};
+use rocket::http::{Cookie, CookieJar, Status};
+use rocket::response::Redirect;
+use rocket::{get, post, Request, State};
+use rocket_dyn_templates::{context, Template};
+use shared_db::{model::CreatePlayerModel, repo::player_repo::PlayerError};
+use uuid::Uuid;
+
+use crate::{
+    guard::{CurrentPlayer, SESSION_COOKIE},
+    schema::CredentialsSchema,
+    AppRepositories,
+};
+
+#[get("/login")]
+pub async fn get_login_ui_handler() -> Template {
+    Template::render("login", context! {})
+}
+
+#[get("/register")]
+pub async fn get_register_ui_handler() -> Template {
+    Template::render("register", context! {})
+}
+
+#[post("/register", data = "<body>")]
+pub async fn register_ui_handler(
+    body: Form<Strict<CredentialsSchema>>,
+    cookies: &CookieJar<'_>,
+    data: &State<AppRepositories>,
+) -> Result<Redirect, Template> {
+    let register_request = body.into_inner().into_inner();
+
+    let player_repo = &data.player_repo;
+    let player_create = CreatePlayerModel {
+        username: register_request.username,
+        password: register_request.password,
+    };
+    let created = match player_repo.create(player_create).await {
+        Ok(player) => player,
+        Err(
+            e @ (PlayerError::UsernameTaken
+            | PlayerError::InvalidUsername
+            | PlayerError::InvalidPassword),
+        ) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Template::render(
+                "error/500",
+                context! {
+                    error: format!("Failed to register player: {}", e),
+                },
+            );
+            return Err(error_response);
+        }
+    };
+
+    start_session(created.id, cookies, data).await
+}
+
+#[post("/login", data = "<body>")]
+pub async fn login_ui_handler(
+    body: Form<Strict<CredentialsSchema>>,
+    cookies: &CookieJar<'_>,
+    data: &State<AppRepositories>,
+) -> Result<Redirect, Template> {
+    let login_request = body.into_inner().into_inner();
+
+    let player_repo = &data.player_repo;
+    let player = match player_repo
+        .verify_credentials(&login_request.username, &login_request.password)
+        .await
+    {
+        Ok(player) => player,
+        Err(PlayerError::InvalidCredentials) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "Invalid username or password".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+    };
+
+    start_session(player.id, cookies, data).await
+}
+
+#[post("/logout")]
+pub async fn logout_ui_handler(
+    player: CurrentPlayer,
+    cookies: &CookieJar<'_>,
+    data: &State<AppRepositories>,
+) -> Result<Redirect, Template> {
+    let session_repo = &data.session_repo;
+    if let Err(e) = session_repo.delete(&player.token).await {
+        let error_response = Template::render(
+            "error/500",
+            context! {
+                error: e.to_string(),
+            },
+        );
+        return Err(error_response);
+    }
+
+    cookies.remove_private(Cookie::from(SESSION_COOKIE));
+    Ok(Redirect::to("/login"))
+}
+
+#[catch(401)]
+pub fn unauthorized_ui_catcher(_req: &Request) -> (Status, Template) {
+    (
+        Status::Unauthorized,
+        Template::render(
+            "error/401",
+            context! {
+                error: "You need to log in to play".to_string(),
+            },
+        ),
+    )
+}
+
+async fn start_session(
+    player_id: Uuid,
+    cookies: &CookieJar<'_>,
+    data: &State<AppRepositories>,
+) -> Result<Redirect, Template> {
+    let session_repo = &data.session_repo;
+    let (token, _) = match session_repo.create(player_id).await {
+        Ok(session) => session,
+        Err(e) => {
+            let error_response = Template::render(
+                "error/500",
+                context! {
+                    error: format!("Failed to create session: {}", e),
+                },
+            );
+            return Err(error_response);
+        }
+    };
+
+    cookies.add_private(Cookie::new(SESSION_COOKIE, token));
+    Ok(Redirect::to("/"))
+}
@@ -0,0 +1,132 @@
+use crate::{
+    guard::{CurrentPlayer, SocketTicket},
+    AppRepositories,
+};
+use rocket::{
+    get, post,
+    request::FlashMessage,
+    response::{Flash, Redirect},
+    State,
+};
+use rocket_dyn_templates::{context, Template};
+use shared_db::service::purchase_service::PurchaseError;
+use uuid::Uuid;
+
+#[get("/research")]
+pub async fn research_ui_handler(
+    player: CurrentPlayer,
+    socket_ticket: SocketTicket,
+    flash: Option<FlashMessage<'_>>,
+    data: &State<AppRepositories>,
+) -> Template {
+    let research_repo = &data.research_repo;
+    let finance_repo = &data.finances_repo;
+
+    let technologies = match research_repo.get_all(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+    let effects = match research_repo.get_effects(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    let balance = match finance_repo.get_available_balance(player.id).await {
+        Ok(bal) => bal,
+        Err(_) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: "Failed to get available balance".to_string(),
+                },
+            );
+        }
+    };
+
+    Template::render(
+        "research",
+        context! {
+            technologies: technologies,
+            effects: effects,
+            balance: balance,
+            notice: flash.map(|flash| flash.message().to_string()),
+            player: player.username,
+            socket_ticket: socket_ticket.0,
+        },
+    )
+}
+
+#[post("/research/<id>")]
+pub async fn purchase_research_ui_handler(
+    id: String,
+    player: CurrentPlayer,
+    data: &State<AppRepositories>,
+) -> Result<Flash<Redirect>, Template> {
+    // validate provided id
+    let uuid = match Uuid::parse_str(&id) {
+        Ok(res) => res,
+        Err(_) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "Invalid ID provided".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+    };
+
+    // research technology and debit its cost atomically
+    let purchase_service = &data.purchase_service;
+    let researched = match purchase_service.purchase_research(player.id, uuid).await {
+        Ok(technology) => technology,
+        Err(
+            e @ (PurchaseError::NotFound
+            | PurchaseError::InsufficientFunds
+            | PurchaseError::MissingPrerequisites),
+        ) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+        Err(PurchaseError::AlreadyPurchased) => {
+            let error_response = Template::render(
+                "error/400",
+                context! {
+                    error: "You have already researched this technology".to_string(),
+                },
+            );
+            return Err(error_response);
+        }
+        Err(e) => {
+            let error_response = Template::render(
+                "error/500",
+                context! {
+                    error: format!("Failed to research technology: {}", e),
+                },
+            );
+            return Err(error_response);
+        }
+    };
+
+    let message = format!("Researched {} for $ {}", researched.name, researched.cost);
+    Ok(Flash::success(Redirect::to("/research"), message))
+}
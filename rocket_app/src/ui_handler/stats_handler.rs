@@ -0,0 +1,77 @@
+use crate::{
+    guard::{CurrentPlayer, SocketTicket},
+    helper::stats_range,
+    schema::StatsQuerySchema,
+    AppRepositories,
+};
+use rocket::{get, State};
+use rocket_dyn_templates::{context, Template};
+use shared_db::{config::game_config, repo::transactions_repo::MoneyTransactionError};
+
+#[get("/stats?<query..>")]
+pub async fn stats_ui_handler(
+    query: StatsQuerySchema,
+    player: CurrentPlayer,
+    socket_ticket: SocketTicket,
+    data: &State<AppRepositories>,
+) -> Template {
+    let (bucket_secs, from, to) = match stats_range(&query) {
+        Ok(res) => res,
+        Err(error) => {
+            return Template::render(
+                "error/400",
+                context! {
+                    error: error,
+                },
+            );
+        }
+    };
+
+    let finance_repo = &data.finances_repo;
+    let cashflow = match finance_repo
+        .get_cashflow(player.id, bucket_secs, from, to)
+        .await
+    {
+        Ok(res) => res,
+        Err(MoneyTransactionError::InvalidFilter) => {
+            return Template::render(
+                "error/400",
+                context! {
+                    error: "Invalid time range provided".to_string(),
+                },
+            );
+        }
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    let platforms = match finance_repo.get_platform_returns(player.id).await {
+        Ok(res) => res,
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    Template::render(
+        "stats",
+        context! {
+            cashflow: cashflow,
+            platforms: platforms,
+            bucket_secs: bucket_secs,
+            tick_period: game_config().tick_period_secs,
+            player: player.username,
+            socket_ticket: socket_ticket.0,
+        },
+    )
+}
@@ -0,0 +1,65 @@
+use crate::{
+    guard::{CurrentPlayer, SocketTicket},
+    helper::transaction_filter,
+    schema::TransactionQuerySchema,
+    AppRepositories,
+};
+use rocket::{get, State};
+use rocket_dyn_templates::{context, Template};
+use shared_db::repo::transactions_repo::MoneyTransactionError;
+
+#[get("/history?<query..>")]
+pub async fn history_handler(
+    query: TransactionQuerySchema,
+    player: CurrentPlayer,
+    socket_ticket: SocketTicket,
+    data: &State<AppRepositories>,
+) -> Template {
+    let filter = match transaction_filter(query.clone()) {
+        Ok(res) => res,
+        Err(error) => {
+            return Template::render(
+                "error/400",
+                context! {
+                    error: error,
+                },
+            );
+        }
+    };
+    let (page, limit) = (filter.page, filter.limit);
+
+    let finance_repo = &data.finances_repo;
+    let history = match finance_repo.get_history(player.id, filter).await {
+        Ok(res) => res,
+        Err(e @ MoneyTransactionError::InvalidFilter) => {
+            return Template::render(
+                "error/400",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+        Err(e) => {
+            return Template::render(
+                "error/500",
+                context! {
+                    error: e.to_string(),
+                },
+            );
+        }
+    };
+
+    Template::render(
+        "history",
+        context! {
+            entries: history.entries,
+            total: history.total,
+            page: page,
+            limit: limit,
+            last_page: (history.total + limit - 1) / limit,
+            query: query,
+            player: player.username,
+            socket_ticket: socket_ticket.0,
+        },
+    )
+}
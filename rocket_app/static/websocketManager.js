@@ -1,8 +1,12 @@
+// must match shared_db::protocol::PROTOCOL_VERSION
+const PROTOCOL_VERSION = 1;
+
 class WebSocketManager {
   constructor(url) {
     if (!WebSocketManager.instance) {
       this.url = url;
       this.socket = new WebSocket(this.url);
+      this.handlers = {};
       WebSocketManager.instance = this;
     }
 
@@ -13,14 +17,36 @@ class WebSocketManager {
     return WebSocketManager.instance || new WebSocketManager(url);
   }
 
+  // register a handler for one server message type, e.g. "balance_update"
+  on(type, handler) {
+    this.handlers[type] = handler;
+    return this;
+  }
+
+  // send a client command, e.g. send("get_balance")
+  send(type, data = {}) {
+    const message = { version: PROTOCOL_VERSION, type: type, ...data };
+    this.socket.send(JSON.stringify(message));
+  }
+
   // handle WebSocket events
-  handleWebSocketEvents(updateCallback) {
+  handleWebSocketEvents() {
+    const handlers = this.handlers;
     this.socket.addEventListener('message', function (event) {
-      updateCallback(event.data);
+      const message = JSON.parse(event.data);
+      if (message.version !== PROTOCOL_VERSION) {
+        console.warn(`Unsupported protocol version ${message.version}`);
+        return;
+      }
+
+      const handler = handlers[message.type];
+      if (handler) {
+        handler(message);
+      }
     });
   }
 
   closeConnection() {
     this.socket.close();
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,447 @@
+use serde::Deserialize;
+use std::{collections::HashSet, sync::OnceLock};
+
+use crate::constants::MAX_CONDITION;
+use crate::model::{
+    AchievementProgressModel, CreateBeerModel, EventKind, Money, UpgradeQuoteModel,
+};
+
+const DEFAULT_CONFIG_PATH: &str = "game_config.toml";
+
+static GAME_CONFIG: OnceLock<GameConfig> = OnceLock::new();
+
+#[derive(Debug)]
+pub enum ConfigError {
+    ReadError(String),
+    ParseError(String),
+    InvalidValue(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::ReadError(e) => write!(f, "Failed to read game config: {}", e),
+            ConfigError::ParseError(e) => write!(f, "Failed to parse game config: {}", e),
+            ConfigError::InvalidValue(e) => write!(f, "Invalid game config: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// How the cost or the profitability gain of an upgrade grows with the level
+/// of the platform, as a multiplier of the platform type's base value.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LevelCurve {
+    /// `1 + slope * level`
+    Linear { slope: f64 },
+    /// `factor ^ level`
+    Exponential { factor: f64 },
+    /// One multiplier per level, the last one also applies to all higher
+    /// levels.
+    Table { multipliers: Vec<f64> },
+}
+
+impl LevelCurve {
+    pub fn multiplier(&self, level: i16) -> f64 {
+        match self {
+            LevelCurve::Linear { slope } => 1.0 + slope * f64::from(level),
+            LevelCurve::Exponential { factor } => factor.powi(i32::from(level)),
+            LevelCurve::Table { multipliers } => {
+                let index = usize::try_from(level).unwrap_or(0);
+                match multipliers.get(index).or(multipliers.last()) {
+                    Some(multiplier) => *multiplier,
+                    None => 1.0,
+                }
+            }
+        }
+    }
+
+    /// The base value scaled for an upgrade from `level`, rounded to a whole
+    /// amount. `None` if the result does not fit into an `i64`.
+    pub fn apply(&self, base: i64, level: i16) -> Option<i64> {
+        let scaled = (base as f64 * self.multiplier(level)).round();
+        if !scaled.is_finite() || scaled.abs() >= i64::MAX as f64 {
+            return None;
+        }
+        Some(scaled as i64)
+    }
+
+    fn validate(&self, name: &str) -> Result<(), ConfigError> {
+        match self {
+            LevelCurve::Linear { slope } => {
+                if !slope.is_finite() || *slope < 0.0 {
+                    return Err(invalid(&format!("{} slope must not be negative", name)));
+                }
+            }
+            LevelCurve::Exponential { factor } => {
+                if !factor.is_finite() || *factor <= 0.0 {
+                    return Err(invalid(&format!("{} factor must be positive", name)));
+                }
+            }
+            LevelCurve::Table { multipliers } => {
+                if multipliers.is_empty() {
+                    return Err(invalid(&format!("{} multipliers must not be empty", name)));
+                }
+                if multipliers.iter().any(|m| !m.is_finite() || *m < 0.0) {
+                    return Err(invalid(&format!(
+                        "{} multipliers must not be negative",
+                        name
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Market events rolled by the tick engine.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarketEventsConfig {
+    /// Chance of a new event starting on a tick while none is running.
+    pub chance_per_tick: f64,
+    /// Fixed seed for reproducible events, a random one if missing.
+    pub seed: Option<u64>,
+    pub kinds: Vec<MarketEventKindConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarketEventKindConfig {
+    pub kind: EventKind,
+    /// Relative chance of this kind being picked.
+    pub weight: u32,
+    pub multiplier: f64,
+    pub duration_secs: i64,
+    /// Hits one random platform type instead of all of them.
+    pub single_type: bool,
+}
+
+/// Oil price random walk, stepped by the tick engine: every tick the price
+/// moves `reversion` of the way back to `base_price` plus uniform noise of
+/// up to `volatility` times the base price, kept within the bounds.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OilMarketConfig {
+    pub base_price: f64,
+    pub reversion: f64,
+    pub volatility: f64,
+    pub min_price: f64,
+    pub max_price: f64,
+    /// Fixed seed for a reproducible price series, a random one if missing.
+    pub seed: Option<u64>,
+}
+
+/// Platform wear and repairs, upkeep is set per platform type.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MaintenanceConfig {
+    /// Condition points a running platform loses per tick, it breaks down
+    /// at zero.
+    pub wear_per_tick: i16,
+    /// Share of the platform type's cost that a repair from zero condition
+    /// costs, smaller repairs cost proportionally less.
+    pub repair_cost_fraction: f64,
+}
+
+/// What a player has to do to unlock an achievement.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AchievementRule {
+    /// Buy `count` platforms in total, sold ones included.
+    PlatformsBought { count: i64 },
+    /// Own `count` platforms at once, of one type if given.
+    PlatformsOwned {
+        count: i64,
+        platform_type: Option<String>,
+    },
+    /// Upgrade a platform to its maximum level.
+    MaxLevel,
+    /// Have at least `amount` available at once.
+    Balance { amount: Money },
+    /// Buy every beer, which wins the game.
+    AllBeers,
+}
+
+impl AchievementRule {
+    pub fn is_met(&self, progress: &AchievementProgressModel) -> bool {
+        match self {
+            AchievementRule::PlatformsBought { count } => progress.platforms_bought >= *count,
+            AchievementRule::PlatformsOwned {
+                count,
+                platform_type,
+            } => {
+                let owned: i64 = match platform_type {
+                    Some(name) => progress.platforms_owned.get(name).copied().unwrap_or(0),
+                    None => progress.platforms_owned.values().sum(),
+                };
+                owned >= *count
+            }
+            AchievementRule::MaxLevel => progress.max_level_reached,
+            AchievementRule::Balance { amount } => progress.balance >= *amount,
+            AchievementRule::AllBeers => progress.all_beers_bought,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AchievementConfig {
+    /// Stored with the unlocks, must not change once players unlocked it.
+    pub key: String,
+    pub title: String,
+    pub description: String,
+    pub rule: AchievementRule,
+    /// Only counts if the rule is met within this long after registering.
+    pub within_secs: Option<i64>,
+}
+
+impl AchievementConfig {
+    pub fn is_met(&self, progress: &AchievementProgressModel) -> bool {
+        // a won game is timed by its last beer purchase, however late it is
+        // checked
+        let playing_secs = match (&self.rule, progress.won_after_secs) {
+            (AchievementRule::AllBeers, Some(secs)) => secs,
+            _ => progress.playing_secs,
+        };
+        let in_time = match self.within_secs {
+            Some(secs) => playing_secs <= secs,
+            None => true,
+        };
+        in_time && self.rule.is_met(progress)
+    }
+}
+
+/// Game balancing, loaded from `game_config.toml` (or the file named by
+/// `GAME_CONFIG_PATH`) when a binary starts.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GameConfig {
+    pub starting_balance: Money,
+    /// Platform type every new player starts with, see the `platform_types`
+    /// table.
+    pub starting_platform_type: String,
+    pub tick_period_secs: i64,
+    /// Offline progress is credited for at most this long, at least one
+    /// tick.
+    pub max_offline_secs: i64,
+    /// Share of the money invested in a platform (purchase and upgrades)
+    /// that is refunded when it is sold.
+    pub sell_refund_fraction: f64,
+    /// Earnings entries older than this are compacted into daily entries.
+    pub earnings_retention_secs: i64,
+    pub compaction_period_secs: i64,
+    /// Cost of upgrading a platform from a level, applied to the upgrade
+    /// cost of its type.
+    pub upgrade_cost_curve: LevelCurve,
+    /// Profitability added by an upgrade from a level, applied to the
+    /// profitability of the platform type.
+    pub upgrade_profit_curve: LevelCurve,
+    pub market_events: MarketEventsConfig,
+    pub oil_market: OilMarketConfig,
+    pub maintenance: MaintenanceConfig,
+    pub beers: Vec<CreateBeerModel>,
+    #[serde(default)]
+    pub achievements: Vec<AchievementConfig>,
+}
+
+impl GameConfig {
+    pub fn from_toml(contents: &str) -> Result<GameConfig, ConfigError> {
+        let config: GameConfig = match toml::from_str(contents) {
+            Ok(config) => config,
+            Err(e) => return Err(ConfigError::ParseError(e.to_string())),
+        };
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn max_offline_periods(&self) -> i64 {
+        self.max_offline_secs / self.tick_period_secs
+    }
+
+    /// Cost and profitability gain of upgrading a platform from `level`,
+    /// given its type's base upgrade cost and profitability. `None` if
+    /// either is out of range.
+    pub fn upgrade_quote(
+        &self,
+        upgrade_cost: i64,
+        profitability: i64,
+        level: i16,
+    ) -> Option<UpgradeQuoteModel> {
+        Some(UpgradeQuoteModel {
+            cost: Money::from(self.upgrade_cost_curve.apply(upgrade_cost, level)?),
+            profitability_gain: self.upgrade_profit_curve.apply(profitability, level)?,
+        })
+    }
+
+    /// Cost of repairing a platform of a type costing `platform_cost` from
+    /// `condition` back to full condition.
+    pub fn repair_cost(&self, platform_cost: i64, condition: i16) -> Money {
+        let damage = f64::from(MAX_CONDITION - condition) / f64::from(MAX_CONDITION);
+        Money::from(platform_cost).share(self.maintenance.repair_cost_fraction * damage)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.starting_balance.is_negative() {
+            return Err(invalid("starting_balance must not be negative"));
+        }
+        if self.tick_period_secs < 1 {
+            return Err(invalid("tick_period_secs must be at least 1"));
+        }
+        // a shorter limit would forfeit every offline period
+        if self.max_offline_secs < self.tick_period_secs {
+            return Err(invalid(
+                "max_offline_secs must be at least tick_period_secs",
+            ));
+        }
+
+        if self.earnings_retention_secs < 0 {
+            return Err(invalid("earnings_retention_secs must not be negative"));
+        }
+        if self.compaction_period_secs < 1 {
+            return Err(invalid("compaction_period_secs must be at least 1"));
+        }
+
+        self.upgrade_cost_curve.validate("upgrade_cost_curve")?;
+        self.upgrade_profit_curve.validate("upgrade_profit_curve")?;
+
+        let events = &self.market_events;
+        if !(0.0..=1.0).contains(&events.chance_per_tick) {
+            return Err(invalid(
+                "market_events.chance_per_tick must be between 0 and 1",
+            ));
+        }
+        if events.chance_per_tick > 0.0 && events.kinds.iter().all(|kind| kind.weight == 0) {
+            return Err(invalid(
+                "market_events.kinds needs a kind with a positive weight",
+            ));
+        }
+        for kind in &events.kinds {
+            if !kind.multiplier.is_finite() || kind.multiplier < 0.0 {
+                return Err(invalid(&format!(
+                    "multiplier of market event {} must not be negative",
+                    kind.kind
+                )));
+            }
+            if kind.duration_secs < 1 {
+                return Err(invalid(&format!(
+                    "duration_secs of market event {} must be at least 1",
+                    kind.kind
+                )));
+            }
+        }
+
+        let market = &self.oil_market;
+        if !market.min_price.is_finite() || market.min_price <= 0.0 {
+            return Err(invalid("oil_market.min_price must be positive"));
+        }
+        if !market.max_price.is_finite()
+            || !(market.min_price..=market.max_price).contains(&market.base_price)
+        {
+            return Err(invalid(
+                "oil_market.base_price must be between min_price and max_price",
+            ));
+        }
+        if !(0.0..=1.0).contains(&market.reversion) {
+            return Err(invalid("oil_market.reversion must be between 0 and 1"));
+        }
+        if !market.volatility.is_finite() || market.volatility < 0.0 {
+            return Err(invalid("oil_market.volatility must not be negative"));
+        }
+
+        if !(0..=MAX_CONDITION).contains(&self.maintenance.wear_per_tick) {
+            return Err(invalid(
+                "maintenance.wear_per_tick must be between 0 and 100",
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.maintenance.repair_cost_fraction) {
+            return Err(invalid(
+                "maintenance.repair_cost_fraction must be between 0 and 1",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.sell_refund_fraction) {
+            return Err(invalid("sell_refund_fraction must be between 0 and 1"));
+        }
+        if self.starting_platform_type.trim().is_empty() {
+            return Err(invalid("starting_platform_type must not be empty"));
+        }
+
+        let mut titles = HashSet::new();
+        for beer in &self.beers {
+            if beer.title.trim().is_empty() {
+                return Err(invalid("beer titles must not be empty"));
+            }
+            if !titles.insert(beer.title.as_str()) {
+                return Err(invalid(&format!("beer {} is listed twice", beer.title)));
+            }
+            if beer.cost < 0 {
+                return Err(invalid(&format!(
+                    "cost of beer {} must not be negative",
+                    beer.title
+                )));
+            }
+        }
+
+        let mut keys = HashSet::new();
+        for achievement in &self.achievements {
+            if achievement.key.trim().is_empty() || achievement.key.len() > 64 {
+                return Err(invalid(
+                    "achievement keys must be between 1 and 64 characters long",
+                ));
+            }
+            if !keys.insert(achievement.key.as_str()) {
+                return Err(invalid(&format!(
+                    "achievement {} is listed twice",
+                    achievement.key
+                )));
+            }
+            if achievement.within_secs.is_some_and(|secs| secs < 1) {
+                return Err(invalid(&format!(
+                    "within_secs of achievement {} must be at least 1",
+                    achievement.key
+                )));
+            }
+            let count = match &achievement.rule {
+                AchievementRule::PlatformsBought { count }
+                | AchievementRule::PlatformsOwned { count, .. } => *count,
+                _ => 1,
+            };
+            if count < 1 {
+                return Err(invalid(&format!(
+                    "count of achievement {} must be at least 1",
+                    achievement.key
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> ConfigError {
+    ConfigError::InvalidValue(message.to_string())
+}
+
+/// Reads and validates the game config, making it available through
+/// `game_config`. Called once by each binary on startup.
+pub fn load_game_config() -> Result<&'static GameConfig, ConfigError> {
+    let path =
+        std::env::var("GAME_CONFIG_PATH").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => return Err(ConfigError::ReadError(format!("{}: {}", path, e))),
+    };
+    let config = GameConfig::from_toml(&contents)?;
+
+    Ok(GAME_CONFIG.get_or_init(|| config))
+}
+
+/// The loaded game config.
+///
+/// # Panics
+///
+/// If `load_game_config` has not been called yet.
+pub fn game_config() -> &'static GameConfig {
+    GAME_CONFIG
+        .get()
+        .expect("game config must be loaded on startup")
+}
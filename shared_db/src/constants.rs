@@ -1,13 +1,29 @@
-pub const MAX_PLATFORM_LEVEL: i16 = 10;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+pub const SESSION_DURATION_SECS: i64 = 7 * 24 * 60 * 60;
+pub const SOCKET_TICKET_DURATION_SECS: i64 = 60;
 
-pub const RIG_PLATFORM_COST: i64 = 1000;
-pub const GROUND_PLATFORM_COST: i64 = 10000;
-pub const PUMP_PLATFORM_COST: i64 = 100000;
+// notification channel the database triggers publish entity changes on
+pub const GAME_CHANGES_CHANNEL: &str = "game_changes";
 
-pub const RIG_PLATFORM_UPGRADE_COST: i64 = 100;
-pub const GROUND_PLATFORM_UPGRADE_COST: i64 = 500;
-pub const PUMP_PLATFORM_UPGRADE_COST: i64 = 1000;
+// transaction history pagination
+pub const HISTORY_PAGE_SIZE: i64 = 20;
+pub const MAX_HISTORY_PAGE_SIZE: i64 = 100;
 
-pub const RIG_PLATFORM_PROFITABILITY: i64 = 5;
-pub const GROUND_PLATFORM_PROFITABILITY: i64 = 15;
-pub const PUMP_PLATFORM_PROFITABILITY: i64 = 50;
+// old earnings entries are rolled into one entry per player and day
+pub const COMPACTION_BUCKET_SECS: i64 = 24 * 60 * 60;
+
+// statistics are aggregated per minute or per hour, for at most this many buckets
+pub const MAX_STATS_BUCKETS: i64 = 1440;
+
+// condition of a new or freshly repaired platform
+pub const MAX_CONDITION: i16 = 100;
+
+// oil price history returned at most, one point per tick
+pub const MAX_OIL_PRICE_POINTS: i64 = 1440;
+
+// leaderboard entries returned by default and at most
+pub const LEADERBOARD_SIZE: i64 = 20;
+pub const MAX_LEADERBOARD_SIZE: i64 = 100;
+
+// platforms bought by one bulk purchase at most
+pub const MAX_BULK_PURCHASE: i64 = 100;
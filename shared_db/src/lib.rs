@@ -1,3 +1,6 @@
+pub mod config;
 pub mod constants;
 pub mod model;
+pub mod protocol;
 pub mod repo;
+pub mod service;
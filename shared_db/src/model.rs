@@ -1,63 +1,398 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
-use sqlx::types::BigDecimal;
 use uuid::Uuid;
 
+/// An amount of in-game money, stored as `BIGINT`. Arithmetic is checked,
+/// operations that would overflow return `None` instead of wrapping.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    sqlx::Type,
+)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub fn new(amount: i64) -> Self {
+        Money(amount)
+    }
+
+    pub fn as_i64(&self) -> i64 {
+        self.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Option<Money> {
+        self.0.checked_mul(factor).map(Money)
+    }
+
+    pub fn checked_sum<I: IntoIterator<Item = Money>>(amounts: I) -> Option<Money> {
+        amounts
+            .into_iter()
+            .try_fold(Money::ZERO, |total, amount| total.checked_add(amount))
+    }
+
+    /// The given share of the amount, rounded down. `fraction` has to be
+    /// between 0 and 1, so the result cannot overflow.
+    pub fn share(self, fraction: f64) -> Money {
+        Money((self.0 as f64 * fraction).floor() as i64)
+    }
+}
+
+impl From<i64> for Money {
+    fn from(amount: i64) -> Self {
+        Money(amount)
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
 #[allow(non_snake_case)]
 pub struct OilPlatformModel {
     pub id: Uuid,
-    pub platform_type: PlatformType,
+    pub player_id: Uuid,
+    pub platform_type: String,
     pub platform_level: i16,
+    /// Barrels of oil produced per period, sold at the oil price of the
+    /// period.
     pub profitability: i64,
     #[serde(rename = "createdAt")]
     pub created_at: Option<i64>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<i64>,
+    /// Wears down while the platform runs, broken down at zero.
+    pub condition: i16,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl OilPlatformModel {
+    pub fn is_broken(&self) -> bool {
+        self.condition <= 0
+    }
+}
+
+/// Current running costs of a platform and the cost of repairing it now.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MaintenanceQuoteModel {
+    pub upkeep_per_period: Money,
+    /// Zero for platforms in full condition.
+    pub repair_cost: Money,
+}
+
+/// Price and profitability gain of a platform's next upgrade.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UpgradeQuoteModel {
+    pub cost: Money,
+    pub profitability_gain: i64,
+}
+
+/// A platform together with what it earned and cost so far.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PlatformOverviewModel {
+    #[serde(flatten)]
+    pub platform: OilPlatformModel,
+    pub lifetime_earnings: Money,
+    pub invested: Money,
+    /// What the platform's barrels earn per period at the current oil price
+    /// and research bonuses, nothing while it is broken down.
+    pub income_per_period: Money,
+    pub maintenance: MaintenanceQuoteModel,
+    /// Seconds until the lifetime earnings cover the investment, zero once
+    /// paid back and `None` for platforms that earn no more than their
+    /// upkeep.
+    pub payback_secs: Option<i64>,
+    /// Income per period less upkeep as a share of the investment, `None`
+    /// for platforms that cost nothing.
+    pub current_yield: Option<f64>,
+    /// `None` once the platform reached its maximum level.
+    pub next_upgrade: Option<UpgradeQuoteModel>,
+}
+
+impl PlatformOverviewModel {
+    pub fn new(
+        platform: OilPlatformModel,
+        lifetime_earnings: Money,
+        invested: Money,
+        next_upgrade: Option<UpgradeQuoteModel>,
+        maintenance: MaintenanceQuoteModel,
+        barrel_price: f64,
+        tick_period_secs: i64,
+    ) -> Self {
+        let outstanding = invested
+            .checked_sub(lifetime_earnings)
+            .unwrap_or(Money::ZERO)
+            .as_i64();
+        let gross_income = if platform.is_broken() {
+            0
+        } else {
+            barrel_value(platform.profitability, barrel_price)
+        };
+        let income = gross_income.saturating_sub(maintenance.upkeep_per_period.as_i64());
+        let payback_secs = if outstanding <= 0 {
+            Some(0)
+        } else if income <= 0 {
+            None
+        } else {
+            // rounded up to whole periods, earnings are credited per period
+            let periods = outstanding / income + i64::from(outstanding % income != 0);
+            periods.checked_mul(tick_period_secs)
+        };
+        let current_yield =
+            (invested > Money::ZERO).then(|| income as f64 / invested.as_i64() as f64);
+
+        PlatformOverviewModel {
+            platform,
+            lifetime_earnings,
+            invested,
+            income_per_period: Money::from(gross_income),
+            maintenance,
+            payback_secs,
+            current_yield,
+            next_upgrade,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
 #[allow(non_snake_case)]
-pub struct CreatePlatformModel {
-    pub platform_type: PlatformType,
+pub struct PlatformTypeModel {
+    pub id: Uuid,
+    pub name: String,
+    pub cost: i64,
+    pub upgrade_cost: i64,
     pub profitability: i64,
+    pub max_level: i16,
+    pub image: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<i64>,
+    /// Charged per period for every running platform of the type.
+    pub upkeep: i64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct UpdatePlatformModel {
-    pub profitability_addition: i64,
+pub struct CreatePlatformTypeModel {
+    pub name: String,
+    pub cost: i64,
+    pub upgrade_cost: i64,
+    pub profitability: i64,
+    pub max_level: i16,
+    pub image: String,
+    pub upkeep: i64,
 }
 
-#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Deserialize, Serialize, sqlx::Type)]
-#[sqlx(type_name = "platform_type", rename_all = "lowercase")]
-pub enum PlatformType {
-    Rig,
-    Ground,
-    Pump,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdatePlatformTypeModel {
+    pub name: Option<String>,
+    pub cost: Option<i64>,
+    pub upgrade_cost: Option<i64>,
+    pub profitability: Option<i64>,
+    pub max_level: Option<i16>,
+    pub image: Option<String>,
+    pub upkeep: Option<i64>,
 }
 
-impl From<String> for PlatformType {
-    fn from(s: String) -> Self {
-        match s.as_str() {
-            "Rig" => PlatformType::Rig,
-            "Ground" => PlatformType::Ground,
-            "Pump" => PlatformType::Pump,
-            _ => panic!("Unknown platform type: {}", s),
+/// What a ledger entry was booked for.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    StartingBalance,
+    Earnings,
+    Upkeep,
+    PlatformPurchase,
+    PlatformUpgrade,
+    PlatformRepair,
+    BeerPurchase,
+    Research,
+    Refund,
+    Adjustment,
+}
+
+impl TransactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::StartingBalance => "starting_balance",
+            TransactionKind::Earnings => "earnings",
+            TransactionKind::Upkeep => "upkeep",
+            TransactionKind::PlatformPurchase => "platform_purchase",
+            TransactionKind::PlatformUpgrade => "platform_upgrade",
+            TransactionKind::PlatformRepair => "platform_repair",
+            TransactionKind::BeerPurchase => "beer_purchase",
+            TransactionKind::Research => "research",
+            TransactionKind::Refund => "refund",
+            TransactionKind::Adjustment => "adjustment",
         }
     }
 }
 
-impl From<&str> for PlatformType {
-    fn from(s: &str) -> Self {
-        PlatformType::from(String::from(s))
+impl std::fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
     }
 }
 
-impl ToString for PlatformType {
-    fn to_string(&self) -> String {
+// the column is restricted by a check constraint, anything else is a manual fix
+impl From<String> for TransactionKind {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "starting_balance" => TransactionKind::StartingBalance,
+            "earnings" => TransactionKind::Earnings,
+            "upkeep" => TransactionKind::Upkeep,
+            "platform_purchase" => TransactionKind::PlatformPurchase,
+            "platform_upgrade" => TransactionKind::PlatformUpgrade,
+            "platform_repair" => TransactionKind::PlatformRepair,
+            "beer_purchase" => TransactionKind::BeerPurchase,
+            "research" => TransactionKind::Research,
+            "refund" => TransactionKind::Refund,
+            _ => TransactionKind::Adjustment,
+        }
+    }
+}
+
+/// Oil price in dollars per barrel, recorded once per tick.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OilPriceModel {
+    #[serde(rename = "recordedAt")]
+    pub recorded_at: i64,
+    pub price: f64,
+}
+
+/// What the given number of barrels earns at `barrel_price`, the oil price
+/// times the player's research multiplier, rounded down like settled
+/// earnings. Saturates instead of overflowing.
+pub fn barrel_value(barrels: i64, barrel_price: f64) -> i64 {
+    (barrels as f64 * barrel_price).floor() as i64
+}
+
+/// Kind of a market event, see `MarketEventModel`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    OilPriceSpike,
+    Storm,
+    Strike,
+    Windfall,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
         match self {
-            PlatformType::Rig => String::from("Rig"),
-            PlatformType::Ground => String::from("Ground"),
-            PlatformType::Pump => String::from("Pump"),
+            EventKind::OilPriceSpike => "oil_price_spike",
+            EventKind::Storm => "storm",
+            EventKind::Strike => "strike",
+            EventKind::Windfall => "windfall",
+        }
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// the column is restricted by a check constraint
+impl From<String> for EventKind {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "storm" => EventKind::Storm,
+            "strike" => EventKind::Strike,
+            "windfall" => EventKind::Windfall,
+            _ => EventKind::OilPriceSpike,
+        }
+    }
+}
+
+/// A market event multiplying the earnings of every platform, or only of the
+/// platforms of `platform_type`, for the periods starting between
+/// `starts_at` and `ends_at`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MarketEventModel {
+    pub id: Uuid,
+    pub kind: EventKind,
+    pub platform_type: Option<String>,
+    pub multiplier: f64,
+    #[serde(rename = "startsAt")]
+    pub starts_at: i64,
+    #[serde(rename = "endsAt")]
+    pub ends_at: i64,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CreateMarketEventModel {
+    pub kind: EventKind,
+    pub platform_type: Option<String>,
+    pub multiplier: f64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
+/// What the `item_id` of a ledger entry points at. Entries booked by the
+/// game itself (earnings, starting balance) have no item.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemType {
+    System,
+    Platform,
+    Beer,
+    Technology,
+}
+
+impl ItemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemType::System => "system",
+            ItemType::Platform => "platform",
+            ItemType::Beer => "beer",
+            ItemType::Technology => "technology",
+        }
+    }
+}
+
+impl std::fmt::Display for ItemType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<String> for ItemType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "platform" => ItemType::Platform,
+            "beer" => ItemType::Beer,
+            "technology" => ItemType::Technology,
+            _ => ItemType::System,
         }
     }
 }
@@ -66,20 +401,87 @@ impl ToString for PlatformType {
 #[allow(non_snake_case)]
 pub struct MoneyTransactionModel {
     pub id: Uuid,
+    pub player_id: Uuid,
+    pub item_id: Uuid,
+    pub amount: Money,
+    pub reduces_balance: bool,
+    pub kind: TransactionKind,
+    pub item_type: ItemType,
+    /// Insertion order, breaks ties between entries of the same second.
+    pub seq: i64,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<i64>,
+}
+
+/// Ledger entry together with the player's balance right after it was booked.
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+#[allow(non_snake_case)]
+pub struct LedgerEntryModel {
+    pub id: Uuid,
+    pub player_id: Uuid,
     pub item_id: Uuid,
-    pub amount: i64,
+    pub amount: Money,
     pub reduces_balance: bool,
+    pub kind: TransactionKind,
+    pub item_type: ItemType,
+    #[serde(rename = "runningBalance")]
+    pub running_balance: Money,
     #[serde(rename = "createdAt")]
     pub created_at: Option<i64>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<i64>,
 }
 
+/// Filters of a transaction history query, the time range is inclusive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionFilterModel {
+    pub page: i64,
+    pub limit: i64,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    pub reduces_balance: Option<bool>,
+    pub item_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerPageModel {
+    pub entries: Vec<LedgerEntryModel>,
+    pub total: i64,
+}
+
+/// Money received and spent within one time bucket.
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+pub struct CashflowBucketModel {
+    #[serde(rename = "bucketStart")]
+    pub bucket_start: i64,
+    pub income: Money,
+    pub spending: Money,
+}
+
+/// What a platform cost so far compared to what it yields per period.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PlatformReturnModel {
+    pub platform_id: Uuid,
+    pub platform_type: String,
+    pub platform_level: i16,
+    pub invested: Money,
+    pub earned: Money,
+    pub income_per_period: Money,
+    pub upkeep_per_period: Money,
+    /// Periods until the income less upkeep covers the investment, `None`
+    /// for platforms that earn no more than their upkeep.
+    pub payback_periods: Option<i64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateMoneyTransactionModel {
     pub item_id: Option<Uuid>,
-    pub amount: i64,
+    pub amount: Money,
     pub reduces_balance: bool,
+    pub kind: TransactionKind,
+    pub item_type: ItemType,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
@@ -97,6 +499,58 @@ pub struct BeerModel {
     pub updated_at: Option<i64>,
 }
 
+/// A technology with its effects and the player's research progress.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TechnologyModel {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub cost: i64,
+    /// Platform type that cannot be bought before this is researched.
+    pub unlocks_platform_type: Option<String>,
+    /// Levels added to the maximum level of every platform type.
+    pub max_level_bonus: i16,
+    /// Added to the earnings multiplier, 0.1 earns 10% more.
+    pub profitability_bonus: f64,
+    /// Names of the technologies to research first.
+    pub prerequisites: Vec<String>,
+    pub researched: bool,
+    /// Whether all prerequisites are researched.
+    pub available: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<i64>,
+}
+
+/// Combined effects of everything a player researched.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ResearchEffectsModel {
+    pub max_level_bonus: i16,
+    /// Applied to all platform earnings, 1.0 without research.
+    pub profitability_multiplier: f64,
+}
+
+impl ResearchEffectsModel {
+    /// Maximum level of a platform type with the base maximum `max_level`,
+    /// kept in range however large the bonus is.
+    pub fn max_level(&self, max_level: i16) -> i16 {
+        max_level.saturating_add(self.max_level_bonus)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+#[allow(non_snake_case)]
+pub struct BeerPurchaseModel {
+    pub id: Uuid,
+    pub player_id: Uuid,
+    pub beer_id: Uuid,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<i64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateBeerModel {
     pub title: String,
@@ -105,7 +559,191 @@ pub struct CreateBeerModel {
     pub cost: i64,
 }
 
-#[derive(sqlx::FromRow)]
-pub struct NumericHandler {
-    pub calculation: Option<BigDecimal>,
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+#[allow(non_snake_case)]
+pub struct PlayerModel {
+    pub id: Uuid,
+    pub username: String,
+    #[serde(skip)]
+    pub password_hash: Option<String>,
+    #[serde(rename = "lastSettledAt")]
+    pub last_settled_at: i64,
+    #[serde(rename = "isAdmin")]
+    pub is_admin: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatePlayerModel {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+#[allow(non_snake_case)]
+pub struct SessionModel {
+    pub id: Uuid,
+    pub player_id: Uuid,
+    #[serde(skip)]
+    pub token_hash: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlatformSaleModel {
+    pub platform: OilPlatformModel,
+    pub refund: Money,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlatformRepairModel {
+    pub platform: OilPlatformModel,
+    pub cost: Money,
+}
+
+/// Outcome of buying several platforms of one type at once, stopping at the
+/// first one the player cannot afford.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkPurchaseModel {
+    pub platforms: Vec<OilPlatformModel>,
+    pub requested: i64,
+    pub total_cost: Money,
+}
+
+/// Outcome of raising a platform by several levels at once, stopping at the
+/// first level the player cannot afford.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkUpgradeModel {
+    pub platform: OilPlatformModel,
+    /// Levels asked for, capped at the platform type's maximum level.
+    pub requested: i64,
+    pub levels: i64,
+    pub total_cost: Money,
+}
+
+/// Outcome of settling a player's earnings up to the current time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SettlementModel {
+    pub periods: i64,
+    pub earned: Money,
+    /// Upkeep debited for the settled periods.
+    pub upkeep: Money,
+}
+
+/// An achievement from the game config and when the player unlocked it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AchievementModel {
+    pub key: String,
+    pub title: String,
+    pub description: String,
+    /// `None` while the achievement is still locked.
+    #[serde(rename = "unlockedAt")]
+    pub unlocked_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+pub struct PlayerAchievementModel {
+    pub id: Uuid,
+    pub player_id: Uuid,
+    pub achievement: String,
+    #[serde(rename = "unlockedAt")]
+    pub unlocked_at: i64,
+}
+
+/// What the achievement rules are checked against.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AchievementProgressModel {
+    /// Seconds from registering to the change being checked.
+    pub playing_secs: i64,
+    /// Seconds from registering to buying the last beer, once the game is
+    /// won.
+    pub won_after_secs: Option<i64>,
+    pub platforms_bought: i64,
+    /// Platforms owned per platform type.
+    pub platforms_owned: HashMap<String, i64>,
+    /// Whether a platform is at its maximum level.
+    pub max_level_reached: bool,
+    pub balance: Money,
+    pub all_beers_bought: bool,
+}
+
+/// A won game, recorded when the player buys the last beer. Totals are
+/// taken from the ledger, earnings include the starting balance.
+#[derive(Debug, Deserialize, Serialize, Clone, sqlx::FromRow)]
+pub struct CompletedGameModel {
+    pub id: Uuid,
+    pub player_id: Uuid,
+    #[serde(rename = "startedAt")]
+    pub started_at: i64,
+    #[serde(rename = "completedAt")]
+    pub completed_at: i64,
+    pub total_earned: Money,
+    pub total_spent: Money,
+    pub beers_spent: Money,
+    pub platforms_bought: i64,
+    pub platforms_owned: i64,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+}
+
+/// How completed games are ranked on the leaderboard.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardOrder {
+    /// Fastest first.
+    Time,
+    /// Highest share of the spending that went into beers first, ties by
+    /// time.
+    Efficiency,
+}
+
+impl LeaderboardOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaderboardOrder::Time => "time",
+            LeaderboardOrder::Efficiency => "efficiency",
+        }
+    }
+}
+
+impl std::fmt::Display for LeaderboardOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LeaderboardEntryModel {
+    pub rank: i64,
+    pub username: String,
+    /// From the first ledger entry to the final beer purchase.
+    pub duration_secs: i64,
+    pub total_earned: Money,
+    pub total_spent: Money,
+    /// Share of the spending that went into beers, between 0 and 1.
+    pub efficiency: f64,
+    pub platforms_bought: i64,
+    pub platforms_owned: i64,
+    #[serde(rename = "completedAt")]
+    pub completed_at: i64,
+}
+
+/// Payload of the notifications sent by the database on game entity changes.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChangeEventModel {
+    pub table: String,
+    pub operation: String,
+    pub id: Uuid,
+    pub player_id: Uuid,
+    pub beer_id: Option<Uuid>,
+    /// When the change was made.
+    pub at: i64,
 }
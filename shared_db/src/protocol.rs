@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::model::{
+    AchievementModel, BeerModel, MarketEventModel, Money, MoneyTransactionModel, OilPlatformModel,
+    OilPriceModel,
+};
+
+/// Bumped whenever a message changes in a way old clients cannot handle.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Every frame on the game state socket is a JSON object carrying the
+/// protocol version and a `type` tag, e.g.
+/// `{"version":1,"type":"balance_update","balance":1005,"just_earned":5,"just_paid_upkeep":1}`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope<T> {
+    pub version: u32,
+    #[serde(flatten)]
+    pub message: T,
+}
+
+/// Messages pushed by the server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// First message after connecting, includes offline progress.
+    Hello {
+        player_id: Uuid,
+        balance: Money,
+        earned_while_away: Money,
+    },
+    BalanceUpdate {
+        balance: Money,
+        just_earned: Money,
+        just_paid_upkeep: Money,
+    },
+    PlatformChanged {
+        platform: OilPlatformModel,
+    },
+    PlatformRemoved {
+        platform_id: Uuid,
+    },
+    BeerPurchased {
+        beer: BeerModel,
+    },
+    TransactionRecorded {
+        transaction: MoneyTransactionModel,
+        balance: Money,
+    },
+    /// Sent to every player when a market event starts.
+    MarketEvent {
+        event: MarketEventModel,
+    },
+    /// Sent to every player when the oil price moves, once per tick.
+    OilPrice {
+        price: OilPriceModel,
+    },
+    AchievementUnlocked {
+        achievement: AchievementModel,
+    },
+    Error {
+        message: String,
+    },
+    Pong,
+}
+
+/// Commands sent by the client.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Ping,
+    /// Asks for a `BalanceUpdate` without waiting for the next tick.
+    GetBalance,
+}
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Malformed,
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ProtocolError::Malformed => write!(f, "Malformed or unknown message"),
+            ProtocolError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported protocol version {}, expected {}",
+                version, PROTOCOL_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl ServerMessage {
+    pub fn to_frame(&self) -> String {
+        let envelope = Envelope {
+            version: PROTOCOL_VERSION,
+            message: self,
+        };
+        serde_json::to_string(&envelope).unwrap_or_default()
+    }
+}
+
+impl ClientMessage {
+    pub fn from_frame(frame: &str) -> Result<ClientMessage, ProtocolError> {
+        let envelope: Envelope<ClientMessage> = match serde_json::from_str(frame) {
+            Ok(envelope) => envelope,
+            Err(_) => return Err(ProtocolError::Malformed),
+        };
+        if envelope.version != PROTOCOL_VERSION {
+            return Err(ProtocolError::UnsupportedVersion(envelope.version));
+        }
+
+        Ok(envelope.message)
+    }
+}
@@ -0,0 +1,225 @@
+use sqlx::{Error as SqlxError, PgPool};
+use uuid::Uuid;
+
+use crate::config::{game_config, AchievementConfig};
+use crate::model::{AchievementModel, AchievementProgressModel, PlayerAchievementModel};
+
+use super::research_repo::research_effects;
+use super::transactions_repo::available_balance;
+
+#[derive(Debug)]
+pub enum AchievementError {
+    NotFound,
+    OtherError,
+}
+
+impl std::fmt::Display for AchievementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            AchievementError::NotFound => write!(f, "Achievement not found"),
+            AchievementError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for AchievementError {}
+
+/// Achievements are defined in the game config, this stores which of them
+/// each player unlocked and when.
+pub struct AchievementRepo {
+    pool: PgPool,
+}
+
+impl AchievementRepo {
+    pub fn new(pool: PgPool) -> Self {
+        AchievementRepo { pool }
+    }
+
+    /// All configured achievements, in config order.
+    pub async fn get_all(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Vec<AchievementModel>, AchievementError> {
+        let unlocks = match sqlx::query_as!(
+            PlayerAchievementModel,
+            "SELECT * FROM player_achievements WHERE player_id = $1",
+            player_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(unlocks) => unlocks,
+            Err(_) => return Err(AchievementError::OtherError),
+        };
+
+        let achievements = game_config()
+            .achievements
+            .iter()
+            .map(|achievement| {
+                let unlocked_at = unlocks
+                    .iter()
+                    .find(|unlock| unlock.achievement == achievement.key)
+                    .map(|unlock| unlock.unlocked_at);
+                achievement_model(achievement, unlocked_at)
+            })
+            .collect();
+        Ok(achievements)
+    }
+
+    /// An unlocked achievement by the id of its unlock.
+    pub async fn get_by_id(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<AchievementModel, AchievementError> {
+        let unlock = match sqlx::query_as!(
+            PlayerAchievementModel,
+            "SELECT * FROM player_achievements WHERE id = $1 AND player_id = $2",
+            id,
+            player_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(unlock) => unlock,
+            Err(SqlxError::RowNotFound) => return Err(AchievementError::NotFound),
+            Err(_) => return Err(AchievementError::OtherError),
+        };
+
+        // unlocks of achievements removed from the config are not shown
+        match game_config()
+            .achievements
+            .iter()
+            .find(|achievement| achievement.key == unlock.achievement)
+        {
+            Some(achievement) => Ok(achievement_model(achievement, Some(unlock.unlocked_at))),
+            None => Err(AchievementError::NotFound),
+        }
+    }
+
+    /// The player's progress towards the achievement rules, timed as of
+    /// `at`.
+    pub async fn get_progress(
+        &self,
+        player_id: Uuid,
+        at: i64,
+    ) -> Result<AchievementProgressModel, AchievementError> {
+        let effects = match research_effects(&self.pool, player_id).await {
+            Ok(effects) => effects,
+            Err(_) => return Err(AchievementError::OtherError),
+        };
+
+        let row = match sqlx::query!(
+            r#"SELECT
+                $3 - COALESCE(p.created_at, 0) AS "playing_secs!",
+                (
+                    SELECT g.completed_at - COALESCE(p.created_at, 0) FROM completed_games g
+                    WHERE g.player_id = $1
+                ) AS won_after_secs,
+                (
+                    SELECT COUNT(*) FROM money_transactions
+                    WHERE player_id = $1 AND kind = 'platform_purchase'
+                ) AS "platforms_bought!",
+                EXISTS (
+                    SELECT 1 FROM oil_platforms o
+                    JOIN platform_types t ON t.name = o.platform_type
+                    WHERE o.player_id = $1 AND o.platform_level >= t.max_level + $2::INTEGER
+                ) AS "max_level_reached!",
+                EXISTS (SELECT 1 FROM beers) AND NOT EXISTS (
+                    SELECT 1 FROM beers b
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM beer_purchases bp WHERE bp.beer_id = b.id AND bp.player_id = $1
+                    )
+                ) AS "all_beers_bought!"
+            FROM players p WHERE p.id = $1"#,
+            player_id,
+            i32::from(effects.max_level_bonus),
+            at
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(row) => row,
+            Err(SqlxError::RowNotFound) => return Err(AchievementError::NotFound),
+            Err(_) => return Err(AchievementError::OtherError),
+        };
+
+        let owned = match sqlx::query!(
+            r#"SELECT platform_type, COUNT(*) AS "count!" FROM oil_platforms
+            WHERE player_id = $1 GROUP BY platform_type"#,
+            player_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(owned) => owned,
+            Err(_) => return Err(AchievementError::OtherError),
+        };
+
+        let balance = match available_balance(&self.pool, player_id).await {
+            Ok(balance) => balance,
+            Err(_) => return Err(AchievementError::OtherError),
+        };
+
+        Ok(AchievementProgressModel {
+            playing_secs: row.playing_secs,
+            won_after_secs: row.won_after_secs,
+            platforms_bought: row.platforms_bought,
+            platforms_owned: owned
+                .into_iter()
+                .map(|row| (row.platform_type, row.count))
+                .collect(),
+            max_level_reached: row.max_level_reached,
+            balance,
+            all_beers_bought: row.all_beers_bought,
+        })
+    }
+
+    /// Stores the unlocks, returning the achievements that were not
+    /// unlocked before.
+    pub async fn unlock(
+        &self,
+        player_id: Uuid,
+        keys: &[String],
+    ) -> Result<Vec<AchievementModel>, AchievementError> {
+        let unlocks = match sqlx::query_as!(
+            PlayerAchievementModel,
+            "INSERT INTO player_achievements (player_id, achievement)
+            SELECT $1, * FROM UNNEST($2::VARCHAR[])
+            ON CONFLICT (player_id, achievement) DO NOTHING
+            RETURNING *",
+            player_id,
+            keys
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(unlocks) => unlocks,
+            Err(_) => return Err(AchievementError::OtherError),
+        };
+
+        let achievements = game_config()
+            .achievements
+            .iter()
+            .filter_map(|achievement| {
+                let unlock = unlocks
+                    .iter()
+                    .find(|unlock| unlock.achievement == achievement.key)?;
+                Some(achievement_model(achievement, Some(unlock.unlocked_at)))
+            })
+            .collect();
+        Ok(achievements)
+    }
+}
+
+fn achievement_model(
+    achievement: &AchievementConfig,
+    unlocked_at: Option<i64>,
+) -> AchievementModel {
+    AchievementModel {
+        key: achievement.key.clone(),
+        title: achievement.title.clone(),
+        description: achievement.description.clone(),
+        unlocked_at,
+    }
+}
@@ -6,7 +6,6 @@ use crate::model::BeerModel;
 #[derive(Debug)]
 pub enum BeerError {
     NotFound,
-    AlreadyPurchased,
     OtherError,
 }
 
@@ -14,9 +13,6 @@ impl std::fmt::Display for BeerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
             BeerError::NotFound => write!(f, "Beer not found"),
-            BeerError::AlreadyPurchased => {
-                write!(f, "Beer already purchased")
-            }
             BeerError::OtherError => write!(f, "Other database-related error"),
         }
     }
@@ -33,10 +29,18 @@ impl BeerRepo {
         BeerRepo { pool }
     }
 
-    pub async fn get_by_id(&self, id: Uuid) -> Result<BeerModel, BeerError> {
-        let query_result = match sqlx::query_as!(BeerModel, "SELECT * FROM beers WHERE id = $1", id)
-            .fetch_one(&self.pool)
-            .await
+    pub async fn get_by_id(&self, player_id: Uuid, id: Uuid) -> Result<BeerModel, BeerError> {
+        let query_result = match sqlx::query_as!(
+            BeerModel,
+            r#"SELECT beers.id, title, description, thumbnail, cost,
+                EXISTS (SELECT 1 FROM beer_purchases WHERE beer_id = beers.id AND player_id = $2) AS purchased,
+                created_at, updated_at
+            FROM beers WHERE id = $1"#,
+            id,
+            player_id
+        )
+        .fetch_one(&self.pool)
+        .await
         {
             Ok(beer) => beer,
             Err(SqlxError::RowNotFound) => return Err(BeerError::NotFound),
@@ -46,43 +50,19 @@ impl BeerRepo {
         Ok(query_result)
     }
 
-    pub async fn get_all(&self) -> Result<Vec<BeerModel>, BeerError> {
-        let query_result = match sqlx::query_as!(BeerModel, "SELECT * FROM beers ORDER BY cost ASC")
-            .fetch_all(&self.pool)
-            .await
-        {
-            Ok(beers) => beers,
-            Err(SqlxError::RowNotFound) => return Err(BeerError::NotFound),
-            Err(_) => return Err(BeerError::OtherError),
-        };
-
-        Ok(query_result)
-    }
-
-    pub async fn purchase(&self, id: Uuid) -> Result<BeerModel, BeerError> {
-        let query_result = match sqlx::query_as!(BeerModel, "SELECT * FROM beers WHERE id = $1", id)
-            .fetch_one(&self.pool)
-            .await
-        {
-            Ok(beer) => beer,
-            Err(SqlxError::RowNotFound) => return Err(BeerError::NotFound),
-            Err(_) => return Err(BeerError::OtherError),
-        };
-
-        if query_result.purchased == Some(true) {
-            return Err(BeerError::AlreadyPurchased);
-        }
-
+    pub async fn get_all(&self, player_id: Uuid) -> Result<Vec<BeerModel>, BeerError> {
         let query_result = match sqlx::query_as!(
             BeerModel,
-            "UPDATE beers SET purchased = $1 WHERE id = $2 RETURNING *",
-            true,
-            id
+            r#"SELECT beers.id, title, description, thumbnail, cost,
+                EXISTS (SELECT 1 FROM beer_purchases WHERE beer_id = beers.id AND player_id = $1) AS purchased,
+                created_at, updated_at
+            FROM beers ORDER BY cost ASC"#,
+            player_id
         )
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
         .await
         {
-            Ok(beer_purchased) => beer_purchased,
+            Ok(beers) => beers,
             Err(SqlxError::RowNotFound) => return Err(BeerError::NotFound),
             Err(_) => return Err(BeerError::OtherError),
         };
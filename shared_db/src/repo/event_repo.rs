@@ -0,0 +1,69 @@
+use sqlx::PgPool;
+
+use crate::model::{CreateMarketEventModel, MarketEventModel};
+
+#[derive(Debug)]
+pub enum MarketEventError {
+    OtherError,
+}
+
+impl std::fmt::Display for MarketEventError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            MarketEventError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for MarketEventError {}
+
+/// Market events are shared by all players and started by the tick engine.
+pub struct MarketEventRepo {
+    pool: PgPool,
+}
+
+impl MarketEventRepo {
+    pub fn new(pool: PgPool) -> Self {
+        MarketEventRepo { pool }
+    }
+
+    /// Events running at the given time, the newest first.
+    pub async fn get_active(&self, at: i64) -> Result<Vec<MarketEventModel>, MarketEventError> {
+        let query_result = match sqlx::query_as!(
+            MarketEventModel,
+            "SELECT * FROM events WHERE starts_at <= $1 AND ends_at > $1 ORDER BY starts_at DESC",
+            at
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(events) => events,
+            Err(_) => return Err(MarketEventError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    pub async fn create(
+        &self,
+        item: CreateMarketEventModel,
+    ) -> Result<MarketEventModel, MarketEventError> {
+        let query_result = match sqlx::query_as!(
+            MarketEventModel,
+            "INSERT INTO events (kind, platform_type, multiplier, starts_at, ends_at) VALUES ($1, $2, $3, $4, $5) RETURNING *",
+            item.kind.as_str(),
+            item.platform_type,
+            item.multiplier,
+            item.starts_at,
+            item.ends_at
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(event) => event,
+            Err(_) => return Err(MarketEventError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+}
@@ -1,16 +1,16 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
+// every game entity belongs to a player, so all operations are scoped by player;
+// changes go through the purchase service, which books them in the ledger
 #[async_trait]
-pub trait Repo<ReturnType, CreateType, UpdateType> {
+pub trait Repo<ReturnType> {
     type Error;
     type Pool: Clone;
 
     fn new(pool: Self::Pool) -> Self;
     fn get_pool(&self) -> Self::Pool;
 
-    async fn get_by_id(&self, id: Uuid) -> Result<ReturnType, Self::Error>;
-    async fn get_all(&self) -> Result<Vec<ReturnType>, Self::Error>;
-    async fn create(&self, item: CreateType) -> Result<ReturnType, Self::Error>;
-    async fn update(&self, id: Uuid, new_item: UpdateType) -> Result<ReturnType, Self::Error>;
+    async fn get_by_id(&self, player_id: Uuid, id: Uuid) -> Result<ReturnType, Self::Error>;
+    async fn get_all(&self, player_id: Uuid) -> Result<Vec<ReturnType>, Self::Error>;
 }
@@ -0,0 +1,155 @@
+use sqlx::{Error as SqlxError, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::constants::MAX_LEADERBOARD_SIZE;
+use crate::model::{CompletedGameModel, LeaderboardEntryModel, LeaderboardOrder};
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    InvalidLimit,
+    OtherError,
+}
+
+impl std::fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            LeaderboardError::InvalidLimit => write!(f, "Invalid number of leaderboard entries"),
+            LeaderboardError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for LeaderboardError {}
+
+/// Games completed by all players. Games are recorded by the purchase
+/// service when the last beer is bought.
+pub struct LeaderboardRepo {
+    pool: PgPool,
+}
+
+impl LeaderboardRepo {
+    pub fn new(pool: PgPool) -> Self {
+        LeaderboardRepo { pool }
+    }
+
+    /// The best `limit` games in the given order.
+    pub async fn get_ranking(
+        &self,
+        order: LeaderboardOrder,
+        limit: i64,
+    ) -> Result<Vec<LeaderboardEntryModel>, LeaderboardError> {
+        if !(1..=MAX_LEADERBOARD_SIZE).contains(&limit) {
+            return Err(LeaderboardError::InvalidLimit);
+        }
+
+        match self.fetch_ranking(order, limit, None).await {
+            Ok(entries) => Ok(entries),
+            Err(_) => Err(LeaderboardError::OtherError),
+        }
+    }
+
+    /// The player's game with its rank in the given order, `None` if the
+    /// player has not won yet.
+    pub async fn get_entry(
+        &self,
+        player_id: Uuid,
+        order: LeaderboardOrder,
+    ) -> Result<Option<LeaderboardEntryModel>, LeaderboardError> {
+        match self.fetch_ranking(order, 1, Some(player_id)).await {
+            Ok(mut entries) => Ok(entries.pop()),
+            Err(_) => Err(LeaderboardError::OtherError),
+        }
+    }
+
+    pub async fn get_completed_game(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Option<CompletedGameModel>, LeaderboardError> {
+        match sqlx::query_as!(
+            CompletedGameModel,
+            "SELECT * FROM completed_games WHERE player_id = $1",
+            player_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        {
+            Ok(game) => Ok(game),
+            Err(_) => Err(LeaderboardError::OtherError),
+        }
+    }
+
+    async fn fetch_ranking(
+        &self,
+        order: LeaderboardOrder,
+        limit: i64,
+        player_id: Option<Uuid>,
+    ) -> Result<Vec<LeaderboardEntryModel>, SqlxError> {
+        // ranks are computed over all games before picking the player's one
+        sqlx::query_as!(
+            LeaderboardEntryModel,
+            r#"SELECT rank AS "rank!", username, duration_secs AS "duration_secs!",
+                total_earned, total_spent, efficiency AS "efficiency!",
+                platforms_bought, platforms_owned, completed_at
+            FROM (
+                SELECT g.player_id, p.username, g.completed_at - g.started_at AS duration_secs,
+                    g.total_earned, g.total_spent,
+                    CAST(g.beers_spent AS DOUBLE PRECISION) / GREATEST(g.total_spent, 1) AS efficiency,
+                    g.platforms_bought, g.platforms_owned, g.completed_at,
+                    ROW_NUMBER() OVER (
+                        ORDER BY
+                            CASE WHEN $1 THEN CAST(g.beers_spent AS DOUBLE PRECISION) / GREATEST(g.total_spent, 1) END DESC,
+                            g.completed_at - g.started_at ASC,
+                            g.completed_at ASC
+                    ) AS rank
+                FROM completed_games g
+                JOIN players p ON p.id = g.player_id
+            ) ranking
+            WHERE $3::UUID IS NULL OR player_id = $3
+            ORDER BY rank ASC
+            LIMIT $2"#,
+            order == LeaderboardOrder::Efficiency,
+            limit,
+            player_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+// executor-generic so that the purchase service can record the game in the
+// transaction of the final beer purchase; does nothing if the player has not
+// bought every beer or the game is already recorded
+pub(crate) async fn record_completed_game<'e, E: PgExecutor<'e>>(
+    executor: E,
+    player_id: Uuid,
+    completed_at: i64,
+) -> Result<(), SqlxError> {
+    sqlx::query!(
+        r#"INSERT INTO completed_games (
+            player_id, started_at, completed_at, total_earned, total_spent, beers_spent,
+            platforms_bought, platforms_owned
+        )
+        SELECT $1, MIN(t.created_at), $2,
+            CAST(COALESCE(SUM(t.amount) FILTER (WHERE NOT t.reduces_balance), 0) AS BIGINT),
+            CAST(COALESCE(SUM(t.amount) FILTER (WHERE t.reduces_balance), 0) AS BIGINT),
+            CAST(COALESCE(SUM(t.amount) FILTER (WHERE t.kind = 'beer_purchase'), 0) AS BIGINT),
+            COUNT(*) FILTER (WHERE t.kind = 'platform_purchase'),
+            (SELECT COUNT(*) FROM oil_platforms WHERE player_id = $1)
+        FROM money_transactions t
+        WHERE t.player_id = $1
+            AND NOT EXISTS (
+                SELECT 1 FROM beers b
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM beer_purchases bp WHERE bp.beer_id = b.id AND bp.player_id = $1
+                )
+            )
+        HAVING COUNT(*) > 0
+        ON CONFLICT (player_id) DO NOTHING"#,
+        player_id,
+        completed_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
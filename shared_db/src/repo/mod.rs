@@ -1,4 +1,12 @@
+pub mod achievement_repo;
 pub mod beer_repo;
+pub mod event_repo;
 pub mod generic;
+pub mod leaderboard_repo;
+pub mod oil_price_repo;
 pub mod platform_repo;
+pub mod platform_type_repo;
+pub mod player_repo;
+pub mod research_repo;
+pub mod session_repo;
 pub mod transactions_repo;
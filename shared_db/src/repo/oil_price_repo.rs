@@ -0,0 +1,111 @@
+use sqlx::PgPool;
+
+use crate::constants::MAX_OIL_PRICE_POINTS;
+use crate::model::OilPriceModel;
+
+#[derive(Debug)]
+pub enum OilPriceError {
+    InvalidRange,
+    OtherError,
+}
+
+impl std::fmt::Display for OilPriceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            OilPriceError::InvalidRange => write!(f, "Invalid time range"),
+            OilPriceError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for OilPriceError {}
+
+/// The oil price series shared by all players.
+pub struct OilPriceRepo {
+    pool: PgPool,
+}
+
+impl OilPriceRepo {
+    pub fn new(pool: PgPool) -> Self {
+        OilPriceRepo { pool }
+    }
+
+    pub async fn get_latest(&self) -> Result<Option<OilPriceModel>, OilPriceError> {
+        match sqlx::query_as!(
+            OilPriceModel,
+            "SELECT * FROM oil_prices ORDER BY recorded_at DESC LIMIT 1"
+        )
+        .fetch_optional(&self.pool)
+        .await
+        {
+            Ok(price) => Ok(price),
+            Err(_) => Err(OilPriceError::OtherError),
+        }
+    }
+
+    /// Prices recorded between `from` and `to` (inclusive), oldest first.
+    /// Longer ranges are cut to the most recent `MAX_OIL_PRICE_POINTS`.
+    pub async fn get_history(
+        &self,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<OilPriceModel>, OilPriceError> {
+        if to < from {
+            return Err(OilPriceError::InvalidRange);
+        }
+
+        let query_result = match sqlx::query_as!(
+            OilPriceModel,
+            r#"SELECT recorded_at AS "recorded_at!", price AS "price!" FROM (
+                SELECT * FROM oil_prices WHERE recorded_at BETWEEN $1 AND $2
+                ORDER BY recorded_at DESC
+                LIMIT $3
+            ) recent
+            ORDER BY recorded_at ASC"#,
+            from,
+            to,
+            MAX_OIL_PRICE_POINTS
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(prices) => prices,
+            Err(_) => return Err(OilPriceError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    pub async fn create(&self, item: OilPriceModel) -> Result<OilPriceModel, OilPriceError> {
+        let query_result = match sqlx::query_as!(
+            OilPriceModel,
+            "INSERT INTO oil_prices (recorded_at, price) VALUES ($1, $2) ON CONFLICT (recorded_at) DO UPDATE SET price = EXCLUDED.price RETURNING *",
+            item.recorded_at,
+            item.price
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(price) => price,
+            Err(_) => return Err(OilPriceError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    /// Drops prices recorded before `before`, except for the latest one,
+    /// which still applies to the periods after it. Returns how many were
+    /// dropped.
+    pub async fn delete_before(&self, before: i64) -> Result<u64, OilPriceError> {
+        match sqlx::query!(
+            "DELETE FROM oil_prices WHERE recorded_at < $1 AND recorded_at < (SELECT MAX(recorded_at) FROM oil_prices)",
+            before
+        )
+        .execute(&self.pool)
+        .await
+        {
+            Ok(result) => Ok(result.rows_affected()),
+            Err(_) => Err(OilPriceError::OtherError),
+        }
+    }
+}
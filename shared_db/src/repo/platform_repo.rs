@@ -3,15 +3,15 @@ use sqlx::Error as SqlxError;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::constants::MAX_PLATFORM_LEVEL;
-use crate::model::{CreatePlatformModel, OilPlatformModel, UpdatePlatformModel};
+use crate::config::game_config;
+use crate::model::{MaintenanceQuoteModel, Money, OilPlatformModel, PlatformOverviewModel};
 
 use super::generic::Repo;
+use super::research_repo::research_effects;
 
 #[derive(Debug)]
 pub enum OilPlatformError {
     NotFound,
-    MaxLevelReached,
     OtherError,
 }
 
@@ -19,9 +19,6 @@ impl std::fmt::Display for OilPlatformError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
             OilPlatformError::NotFound => write!(f, "Platform not found"),
-            OilPlatformError::MaxLevelReached => {
-                write!(f, "Maximum upgrade level of platform reached")
-            }
             OilPlatformError::OtherError => write!(f, "Other database-related error"),
         }
     }
@@ -33,81 +30,141 @@ pub struct OilPlaftormRepo {
     pool: PgPool,
 }
 
-#[async_trait]
-impl Repo<OilPlatformModel, CreatePlatformModel, UpdatePlatformModel> for OilPlaftormRepo {
-    type Error = OilPlatformError;
-    type Pool = PgPool;
-
-    fn new(pool: Self::Pool) -> Self {
-        OilPlaftormRepo { pool }
+impl OilPlaftormRepo {
+    /// All platforms of the player with their lifetime earnings,
+    /// investment and maintenance costs.
+    pub async fn get_overviews(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Vec<PlatformOverviewModel>, OilPlatformError> {
+        self.fetch_overviews(player_id, None).await
     }
 
-    fn get_pool(&self) -> Self::Pool {
-        self.pool.clone()
+    pub async fn get_overview(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<PlatformOverviewModel, OilPlatformError> {
+        match self.fetch_overviews(player_id, Some(id)).await?.pop() {
+            Some(overview) => Ok(overview),
+            None => Err(OilPlatformError::NotFound),
+        }
     }
 
-    async fn get_by_id(&self, id: Uuid) -> Result<OilPlatformModel, OilPlatformError> {
-        let query_result = match sqlx::query_as!(
-            OilPlatformModel,
-            "SELECT * FROM oil_platforms WHERE id = $1",
-            id
-        )
-        .fetch_one(&self.pool)
-        .await
-        {
-            Ok(platform) => platform,
-            Err(SqlxError::RowNotFound) => return Err(OilPlatformError::NotFound),
+    async fn fetch_overviews(
+        &self,
+        player_id: Uuid,
+        id: Option<Uuid>,
+    ) -> Result<Vec<PlatformOverviewModel>, OilPlatformError> {
+        let effects = match research_effects(&self.pool, player_id).await {
+            Ok(effects) => effects,
             Err(_) => return Err(OilPlatformError::OtherError),
         };
 
-        Ok(query_result)
-    }
-
-    async fn get_all(&self) -> Result<Vec<OilPlatformModel>, OilPlatformError> {
-        let query_result = match sqlx::query_as!(
-            OilPlatformModel,
-            "SELECT * FROM oil_platforms ORDER BY created_at ASC"
+        let rows = match sqlx::query!(
+            r#"SELECT p.id, p.player_id, p.platform_type, p.platform_level, p.profitability, p.created_at, p.updated_at, p.condition,
+                CAST(COALESCE(e.earned, 0) AS BIGINT) AS "earned!: Money",
+                CAST(COALESCE(i.invested, 0) AS BIGINT) AS "invested!: Money",
+                t.upgrade_cost AS type_upgrade_cost, t.profitability AS type_profitability, t.max_level,
+                t.cost AS type_cost, t.upkeep AS type_upkeep,
+                COALESCE((
+                    SELECT price FROM oil_prices ORDER BY recorded_at DESC LIMIT 1
+                ), $3) AS "oil_price!"
+            FROM oil_platforms p
+            JOIN platform_types t ON t.name = p.platform_type
+            LEFT JOIN (
+                SELECT platform_id, SUM(amount) AS earned FROM platform_earnings
+                WHERE player_id = $1 GROUP BY platform_id
+            ) e ON e.platform_id = p.id
+            LEFT JOIN (
+                SELECT item_id, SUM(amount) AS invested FROM money_transactions
+                WHERE player_id = $1 AND kind IN ('platform_purchase', 'platform_upgrade')
+                GROUP BY item_id
+            ) i ON i.item_id = p.id
+            WHERE p.player_id = $1 AND ($2::UUID IS NULL OR p.id = $2)
+            ORDER BY p.created_at ASC"#,
+            player_id,
+            id,
+            game_config().oil_market.base_price
         )
         .fetch_all(&self.pool)
         .await
         {
-            Ok(platforms) => platforms,
-            Err(SqlxError::RowNotFound) => return Err(OilPlatformError::NotFound),
+            Ok(rows) => rows,
             Err(_) => return Err(OilPlatformError::OtherError),
         };
 
-        Ok(query_result)
+        let config = game_config();
+        let overviews = rows
+            .into_iter()
+            .map(|row| {
+                let next_upgrade = if row.platform_level < effects.max_level(row.max_level) {
+                    config.upgrade_quote(
+                        row.type_upgrade_cost,
+                        row.type_profitability,
+                        row.platform_level,
+                    )
+                } else {
+                    None
+                };
+                // broken platforms cost no upkeep until repaired
+                let upkeep = if row.condition > 0 {
+                    row.type_upkeep
+                } else {
+                    0
+                };
+                let maintenance = MaintenanceQuoteModel {
+                    upkeep_per_period: Money::from(upkeep),
+                    repair_cost: config.repair_cost(row.type_cost, row.condition),
+                };
+                let platform = OilPlatformModel {
+                    id: row.id,
+                    player_id: row.player_id,
+                    platform_type: row.platform_type,
+                    platform_level: row.platform_level,
+                    profitability: row.profitability,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                    condition: row.condition,
+                };
+                PlatformOverviewModel::new(
+                    platform,
+                    row.earned,
+                    row.invested,
+                    next_upgrade,
+                    maintenance,
+                    row.oil_price * effects.profitability_multiplier,
+                    config.tick_period_secs,
+                )
+            })
+            .collect();
+        Ok(overviews)
     }
+}
 
-    async fn create(
-        &self,
-        item: CreatePlatformModel,
-    ) -> Result<OilPlatformModel, OilPlatformError> {
-        let query_result = match sqlx::query_as!(
-            OilPlatformModel,
-            "INSERT INTO oil_platforms (platform_type, profitability) VALUES ($1, $2) RETURNING *",
-            item.platform_type.to_string(),
-            item.profitability,
-        )
-        .fetch_one(&self.pool)
-        .await
-        {
-            Ok(platform) => platform,
-            Err(_) => return Err(OilPlatformError::OtherError),
-        };
+#[async_trait]
+impl Repo<OilPlatformModel> for OilPlaftormRepo {
+    type Error = OilPlatformError;
+    type Pool = PgPool;
 
-        Ok(query_result)
+    fn new(pool: Self::Pool) -> Self {
+        OilPlaftormRepo { pool }
     }
 
-    async fn update(
+    fn get_pool(&self) -> Self::Pool {
+        self.pool.clone()
+    }
+
+    async fn get_by_id(
         &self,
+        player_id: Uuid,
         id: Uuid,
-        new_item: UpdatePlatformModel,
     ) -> Result<OilPlatformModel, OilPlatformError> {
         let query_result = match sqlx::query_as!(
             OilPlatformModel,
-            "SELECT * FROM oil_platforms WHERE id = $1",
-            id
+            "SELECT * FROM oil_platforms WHERE id = $1 AND player_id = $2",
+            id,
+            player_id
         )
         .fetch_one(&self.pool)
         .await
@@ -117,21 +174,19 @@ impl Repo<OilPlatformModel, CreatePlatformModel, UpdatePlatformModel> for OilPla
             Err(_) => return Err(OilPlatformError::OtherError),
         };
 
-        if query_result.platform_level == MAX_PLATFORM_LEVEL {
-            return Err(OilPlatformError::MaxLevelReached);
-        }
+        Ok(query_result)
+    }
 
+    async fn get_all(&self, player_id: Uuid) -> Result<Vec<OilPlatformModel>, OilPlatformError> {
         let query_result = match sqlx::query_as!(
             OilPlatformModel,
-            "UPDATE oil_platforms SET platform_level = $1, profitability = $2, updated_at = $3 WHERE id = $4 RETURNING *",
-            query_result.platform_level + 1,
-            query_result.profitability + new_item.profitability_addition,
-            chrono::Utc::now().timestamp(),
-            id
+            "SELECT * FROM oil_platforms WHERE player_id = $1 ORDER BY created_at ASC",
+            player_id
         )
-        .fetch_one(&self.pool)
-        .await {
-            Ok(platform_upgraded) => platform_upgraded,
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(platforms) => platforms,
             Err(SqlxError::RowNotFound) => return Err(OilPlatformError::NotFound),
             Err(_) => return Err(OilPlatformError::OtherError),
         };
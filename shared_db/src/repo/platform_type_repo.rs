@@ -0,0 +1,185 @@
+use sqlx::{Error as SqlxError, PgPool};
+
+use crate::model::{CreatePlatformTypeModel, PlatformTypeModel, UpdatePlatformTypeModel};
+
+#[derive(Debug)]
+pub enum PlatformTypeError {
+    NotFound,
+    NameTaken,
+    InUse,
+    InvalidValue(String),
+    OtherError,
+}
+
+impl std::fmt::Display for PlatformTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlatformTypeError::NotFound => write!(f, "Platform type not found"),
+            PlatformTypeError::NameTaken => write!(f, "Platform type name is already taken"),
+            PlatformTypeError::InUse => {
+                write!(f, "Platform type is still used by existing platforms")
+            }
+            PlatformTypeError::InvalidValue(e) => write!(f, "Invalid platform type: {}", e),
+            PlatformTypeError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for PlatformTypeError {}
+
+/// Platform types are shared by all players and managed by admins.
+pub struct PlatformTypeRepo {
+    pool: PgPool,
+}
+
+impl PlatformTypeRepo {
+    pub fn new(pool: PgPool) -> Self {
+        PlatformTypeRepo { pool }
+    }
+
+    pub async fn get_by_name(&self, name: &str) -> Result<PlatformTypeModel, PlatformTypeError> {
+        let query_result = match sqlx::query_as!(
+            PlatformTypeModel,
+            "SELECT * FROM platform_types WHERE name = $1",
+            name
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(platform_type) => platform_type,
+            Err(SqlxError::RowNotFound) => return Err(PlatformTypeError::NotFound),
+            Err(_) => return Err(PlatformTypeError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    /// All platform types, cheapest first.
+    pub async fn get_all(&self) -> Result<Vec<PlatformTypeModel>, PlatformTypeError> {
+        let query_result = match sqlx::query_as!(
+            PlatformTypeModel,
+            "SELECT * FROM platform_types ORDER BY cost ASC, name ASC"
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(platform_types) => platform_types,
+            Err(_) => return Err(PlatformTypeError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    pub async fn create(
+        &self,
+        item: CreatePlatformTypeModel,
+    ) -> Result<PlatformTypeModel, PlatformTypeError> {
+        validate(&item)?;
+
+        let query_result = match sqlx::query_as!(
+            PlatformTypeModel,
+            "INSERT INTO platform_types (name, cost, upgrade_cost, profitability, max_level, image, upkeep) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+            item.name.trim(),
+            item.cost,
+            item.upgrade_cost,
+            item.profitability,
+            item.max_level,
+            item.image,
+            item.upkeep,
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(platform_type) => platform_type,
+            Err(SqlxError::Database(e)) if e.is_unique_violation() => {
+                return Err(PlatformTypeError::NameTaken)
+            }
+            Err(_) => return Err(PlatformTypeError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    /// Changes the given fields of a platform type. Existing platforms keep
+    /// their profitability, new values apply to purchases and upgrades, and
+    /// a new upkeep to every platform of the type.
+    pub async fn update(
+        &self,
+        name: &str,
+        new_item: UpdatePlatformTypeModel,
+    ) -> Result<PlatformTypeModel, PlatformTypeError> {
+        let retrieved = self.get_by_name(name).await?;
+        let updated = CreatePlatformTypeModel {
+            name: new_item.name.unwrap_or(retrieved.name),
+            cost: new_item.cost.unwrap_or(retrieved.cost),
+            upgrade_cost: new_item.upgrade_cost.unwrap_or(retrieved.upgrade_cost),
+            profitability: new_item.profitability.unwrap_or(retrieved.profitability),
+            max_level: new_item.max_level.unwrap_or(retrieved.max_level),
+            image: new_item.image.unwrap_or(retrieved.image),
+            upkeep: new_item.upkeep.unwrap_or(retrieved.upkeep),
+        };
+        validate(&updated)?;
+
+        let query_result = match sqlx::query_as!(
+            PlatformTypeModel,
+            "UPDATE platform_types SET name = $1, cost = $2, upgrade_cost = $3, profitability = $4, max_level = $5, image = $6, upkeep = $7, updated_at = $8 WHERE id = $9 RETURNING *",
+            updated.name.trim(),
+            updated.cost,
+            updated.upgrade_cost,
+            updated.profitability,
+            updated.max_level,
+            updated.image,
+            updated.upkeep,
+            chrono::Utc::now().timestamp(),
+            retrieved.id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(platform_type) => platform_type,
+            Err(SqlxError::RowNotFound) => return Err(PlatformTypeError::NotFound),
+            Err(SqlxError::Database(e)) if e.is_unique_violation() => {
+                return Err(PlatformTypeError::NameTaken)
+            }
+            Err(_) => return Err(PlatformTypeError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<(), PlatformTypeError> {
+        match sqlx::query!("DELETE FROM platform_types WHERE name = $1", name)
+            .execute(&self.pool)
+            .await
+        {
+            Ok(res) if res.rows_affected() == 0 => Err(PlatformTypeError::NotFound),
+            Ok(_) => Ok(()),
+            Err(SqlxError::Database(e)) if e.is_foreign_key_violation() => {
+                Err(PlatformTypeError::InUse)
+            }
+            Err(_) => Err(PlatformTypeError::OtherError),
+        }
+    }
+}
+
+fn validate(item: &CreatePlatformTypeModel) -> Result<(), PlatformTypeError> {
+    let invalid = |message: &str| Err(PlatformTypeError::InvalidValue(message.to_string()));
+
+    if item.name.trim().is_empty() {
+        return invalid("name must not be empty");
+    }
+    if item.cost < 0 || item.upgrade_cost < 0 || item.upkeep < 0 {
+        return invalid("costs must not be negative");
+    }
+    if item.profitability < 0 {
+        return invalid("profitability must not be negative");
+    }
+    if item.max_level < 1 {
+        return invalid("max_level must be at least 1");
+    }
+    if item.image.trim().is_empty() {
+        return invalid("image must not be empty");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use sqlx::{Error as SqlxError, PgPool};
+use uuid::Uuid;
+
+use crate::config::game_config;
+use crate::constants::MIN_PASSWORD_LENGTH;
+use crate::model::{
+    CreateMoneyTransactionModel, CreatePlayerModel, ItemType, OilPlatformModel, PlayerModel,
+    TransactionKind,
+};
+
+use super::transactions_repo::insert_transaction;
+
+#[derive(Debug)]
+pub enum PlayerError {
+    NotFound,
+    UsernameTaken,
+    InvalidUsername,
+    InvalidPassword,
+    InvalidCredentials,
+    OtherError,
+}
+
+impl std::fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PlayerError::NotFound => write!(f, "Player not found"),
+            PlayerError::UsernameTaken => write!(f, "Username is already taken"),
+            PlayerError::InvalidUsername => write!(f, "Username must not be empty"),
+            PlayerError::InvalidPassword => write!(
+                f,
+                "Password must be at least {} characters long",
+                MIN_PASSWORD_LENGTH
+            ),
+            PlayerError::InvalidCredentials => write!(f, "Invalid username or password"),
+            PlayerError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+pub struct PlayerRepo {
+    pool: PgPool,
+}
+
+impl PlayerRepo {
+    pub fn new(pool: PgPool) -> Self {
+        PlayerRepo { pool }
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<PlayerModel, PlayerError> {
+        let query_result =
+            match sqlx::query_as!(PlayerModel, "SELECT * FROM players WHERE id = $1", id)
+                .fetch_one(&self.pool)
+                .await
+            {
+                Ok(player) => player,
+                Err(SqlxError::RowNotFound) => return Err(PlayerError::NotFound),
+                Err(_) => return Err(PlayerError::OtherError),
+            };
+
+        Ok(query_result)
+    }
+
+    /// Checks a username and password pair, returning the matching player.
+    pub async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<PlayerModel, PlayerError> {
+        let query_result = match sqlx::query_as!(
+            PlayerModel,
+            "SELECT * FROM players WHERE username = $1",
+            username.trim()
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(player) => player,
+            Err(SqlxError::RowNotFound) => return Err(PlayerError::InvalidCredentials),
+            Err(_) => return Err(PlayerError::OtherError),
+        };
+
+        let stored_hash = match &query_result.password_hash {
+            Some(hash) => hash,
+            None => return Err(PlayerError::InvalidCredentials),
+        };
+        let parsed_hash = match PasswordHash::new(stored_hash) {
+            Ok(hash) => hash,
+            Err(_) => return Err(PlayerError::OtherError),
+        };
+        match Argon2::default().verify_password(password.as_bytes(), &parsed_hash) {
+            Ok(()) => Ok(query_result),
+            Err(_) => Err(PlayerError::InvalidCredentials),
+        }
+    }
+
+    /// Registers a player and sets up their new game: a starting platform
+    /// and the starting balance.
+    pub async fn create(&self, item: CreatePlayerModel) -> Result<PlayerModel, PlayerError> {
+        let username = item.username.trim();
+        if username.is_empty() {
+            return Err(PlayerError::InvalidUsername);
+        }
+        if item.password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(PlayerError::InvalidPassword);
+        }
+
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = match Argon2::default().hash_password(item.password.as_bytes(), &salt) {
+            Ok(hash) => hash.to_string(),
+            Err(_) => return Err(PlayerError::OtherError),
+        };
+
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return Err(PlayerError::OtherError),
+        };
+
+        let created = match sqlx::query_as!(
+            PlayerModel,
+            "INSERT INTO players (username, password_hash) VALUES ($1, $2) RETURNING *",
+            username,
+            password_hash
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(player) => player,
+            Err(SqlxError::Database(e)) if e.is_unique_violation() => {
+                return Err(PlayerError::UsernameTaken)
+            }
+            Err(_) => return Err(PlayerError::OtherError),
+        };
+
+        if sqlx::query_as!(
+            OilPlatformModel,
+            "INSERT INTO oil_platforms (player_id, platform_type, profitability) SELECT $1, name, profitability FROM platform_types WHERE name = $2 RETURNING *",
+            created.id,
+            game_config().starting_platform_type,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .is_err()
+        {
+            return Err(PlayerError::OtherError);
+        }
+
+        let starting_balance_tx = CreateMoneyTransactionModel {
+            item_id: None,
+            amount: game_config().starting_balance,
+            reduces_balance: false,
+            kind: TransactionKind::StartingBalance,
+            item_type: ItemType::System,
+        };
+        if insert_transaction(&mut *tx, created.id, starting_balance_tx)
+            .await
+            .is_err()
+        {
+            return Err(PlayerError::OtherError);
+        }
+
+        match tx.commit().await {
+            Ok(_) => Ok(created),
+            Err(_) => Err(PlayerError::OtherError),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use sqlx::{Error as SqlxError, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::model::{ResearchEffectsModel, TechnologyModel};
+
+#[derive(Debug)]
+pub enum ResearchError {
+    NotFound,
+    OtherError,
+}
+
+impl std::fmt::Display for ResearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ResearchError::NotFound => write!(f, "Technology not found"),
+            ResearchError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for ResearchError {}
+
+/// The technology tree shared by all players and what each of them
+/// researched. Research is bought through the purchase service.
+pub struct ResearchRepo {
+    pool: PgPool,
+}
+
+impl ResearchRepo {
+    pub fn new(pool: PgPool) -> Self {
+        ResearchRepo { pool }
+    }
+
+    pub async fn get_by_id(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<TechnologyModel, ResearchError> {
+        match fetch_technologies(&self.pool, player_id, Some(id)).await {
+            Ok(mut technologies) => technologies.pop().ok_or(ResearchError::NotFound),
+            Err(_) => Err(ResearchError::OtherError),
+        }
+    }
+
+    /// All technologies, cheapest first.
+    pub async fn get_all(&self, player_id: Uuid) -> Result<Vec<TechnologyModel>, ResearchError> {
+        match fetch_technologies(&self.pool, player_id, None).await {
+            Ok(technologies) => Ok(technologies),
+            Err(_) => Err(ResearchError::OtherError),
+        }
+    }
+
+    pub async fn get_effects(
+        &self,
+        player_id: Uuid,
+    ) -> Result<ResearchEffectsModel, ResearchError> {
+        match research_effects(&self.pool, player_id).await {
+            Ok(effects) => Ok(effects),
+            Err(_) => Err(ResearchError::OtherError),
+        }
+    }
+
+    /// Names of the platform types the player cannot buy yet.
+    pub async fn get_locked_platform_types(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Vec<String>, ResearchError> {
+        match locked_platform_types(&self.pool, player_id, None).await {
+            Ok(names) => Ok(names),
+            Err(_) => Err(ResearchError::OtherError),
+        }
+    }
+}
+
+// executor-generic queries, shared with the other repositories and the
+// service layer so that they can also run inside a database transaction
+pub(crate) async fn fetch_technologies<'e, E: PgExecutor<'e>>(
+    executor: E,
+    player_id: Uuid,
+    id: Option<Uuid>,
+) -> Result<Vec<TechnologyModel>, SqlxError> {
+    sqlx::query_as!(
+        TechnologyModel,
+        r#"SELECT t.id, t.name, t.description, t.cost, t.unlocks_platform_type,
+            t.max_level_bonus, t.profitability_bonus, t.created_at, t.updated_at,
+            ARRAY(
+                SELECT p.name FROM technology_prerequisites tp
+                JOIN technologies p ON p.id = tp.prerequisite_id
+                WHERE tp.technology_id = t.id
+                ORDER BY p.cost ASC, p.name ASC
+            ) AS "prerequisites!",
+            EXISTS (
+                SELECT 1 FROM player_research r
+                WHERE r.technology_id = t.id AND r.player_id = $1
+            ) AS "researched!",
+            NOT EXISTS (
+                SELECT 1 FROM technology_prerequisites tp
+                WHERE tp.technology_id = t.id AND NOT EXISTS (
+                    SELECT 1 FROM player_research r
+                    WHERE r.technology_id = tp.prerequisite_id AND r.player_id = $1
+                )
+            ) AS "available!"
+        FROM technologies t
+        WHERE $2::UUID IS NULL OR t.id = $2
+        ORDER BY t.cost ASC, t.name ASC"#,
+        player_id,
+        id
+    )
+    .fetch_all(executor)
+    .await
+}
+
+pub(crate) async fn research_effects<'e, E: PgExecutor<'e>>(
+    executor: E,
+    player_id: Uuid,
+) -> Result<ResearchEffectsModel, SqlxError> {
+    sqlx::query_as!(
+        ResearchEffectsModel,
+        r#"SELECT CAST(LEAST(COALESCE(SUM(t.max_level_bonus), 0), 32767) AS SMALLINT) AS "max_level_bonus!",
+            1 + COALESCE(SUM(t.profitability_bonus), 0) AS "profitability_multiplier!"
+        FROM player_research r
+        JOIN technologies t ON t.id = r.technology_id
+        WHERE r.player_id = $1"#,
+        player_id
+    )
+    .fetch_one(executor)
+    .await
+}
+
+// a platform type is locked while technologies unlock it and the player has
+// researched none of them
+pub(crate) async fn locked_platform_types<'e, E: PgExecutor<'e>>(
+    executor: E,
+    player_id: Uuid,
+    name: Option<&str>,
+) -> Result<Vec<String>, SqlxError> {
+    sqlx::query_scalar!(
+        r#"SELECT pt.name FROM platform_types pt
+        WHERE ($2::VARCHAR IS NULL OR pt.name = $2)
+            AND EXISTS (SELECT 1 FROM technologies t WHERE t.unlocks_platform_type = pt.name)
+            AND NOT EXISTS (
+                SELECT 1 FROM technologies t
+                JOIN player_research r ON r.technology_id = t.id AND r.player_id = $1
+                WHERE t.unlocks_platform_type = pt.name
+            )
+        ORDER BY pt.cost ASC, pt.name ASC"#,
+        player_id,
+        name
+    )
+    .fetch_all(executor)
+    .await
+}
@@ -0,0 +1,187 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use sqlx::{Error as SqlxError, PgPool};
+use uuid::Uuid;
+
+use crate::constants::{SESSION_DURATION_SECS, SOCKET_TICKET_DURATION_SECS};
+use crate::model::{PlayerModel, SessionModel};
+
+#[derive(Debug)]
+pub enum SessionError {
+    NotFound,
+    Expired,
+    OtherError,
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            SessionError::NotFound => write!(f, "Session not found"),
+            SessionError::Expired => write!(f, "Session expired"),
+            SessionError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+pub struct SessionRepo {
+    pool: PgPool,
+}
+
+impl SessionRepo {
+    pub fn new(pool: PgPool) -> Self {
+        SessionRepo { pool }
+    }
+
+    /// Opens a session for the player. Returns the plain token, which is only
+    /// stored hashed and therefore cannot be recovered later.
+    pub async fn create(&self, player_id: Uuid) -> Result<(String, SessionModel), SessionError> {
+        let token = random_token();
+
+        let query_result = match sqlx::query_as!(
+            SessionModel,
+            "INSERT INTO sessions (player_id, token_hash, expires_at) VALUES ($1, $2, $3) RETURNING *",
+            player_id,
+            hash_token(&token),
+            chrono::Utc::now().timestamp() + SESSION_DURATION_SECS,
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(session) => session,
+            Err(_) => return Err(SessionError::OtherError),
+        };
+
+        Ok((token, query_result))
+    }
+
+    pub async fn get_player_by_token(&self, token: &str) -> Result<PlayerModel, SessionError> {
+        let session = match sqlx::query_as!(
+            SessionModel,
+            "SELECT * FROM sessions WHERE token_hash = $1",
+            hash_token(token)
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(session) => session,
+            Err(SqlxError::RowNotFound) => return Err(SessionError::NotFound),
+            Err(_) => return Err(SessionError::OtherError),
+        };
+
+        if session.expires_at < chrono::Utc::now().timestamp() {
+            return Err(SessionError::Expired);
+        }
+
+        let query_result = match sqlx::query_as!(
+            PlayerModel,
+            "SELECT * FROM players WHERE id = $1",
+            session.player_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(player) => player,
+            Err(SqlxError::RowNotFound) => return Err(SessionError::NotFound),
+            Err(_) => return Err(SessionError::OtherError),
+        };
+
+        Ok(query_result)
+    }
+
+    pub async fn delete(&self, token: &str) -> Result<(), SessionError> {
+        match sqlx::query!(
+            "DELETE FROM sessions WHERE token_hash = $1",
+            hash_token(token)
+        )
+        .execute(&self.pool)
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(_) => Err(SessionError::OtherError),
+        }
+    }
+
+    /// Issues a ticket that opens one websocket for the player within a
+    /// minute. Like session tokens, tickets are only stored hashed.
+    pub async fn create_socket_ticket(&self, player_id: Uuid) -> Result<String, SessionError> {
+        let ticket = random_token();
+
+        match sqlx::query!(
+            "INSERT INTO socket_tickets (player_id, ticket_hash, expires_at) VALUES ($1, $2, $3)",
+            player_id,
+            hash_token(&ticket),
+            chrono::Utc::now().timestamp() + SOCKET_TICKET_DURATION_SECS,
+        )
+        .execute(&self.pool)
+        .await
+        {
+            Ok(_) => Ok(ticket),
+            Err(_) => Err(SessionError::OtherError),
+        }
+    }
+
+    /// Uses up the ticket, returning the player it was issued to.
+    pub async fn redeem_socket_ticket(&self, ticket: &str) -> Result<PlayerModel, SessionError> {
+        let redeemed = match sqlx::query!(
+            "DELETE FROM socket_tickets WHERE ticket_hash = $1 RETURNING player_id, expires_at",
+            hash_token(ticket)
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(redeemed) => redeemed,
+            Err(SqlxError::RowNotFound) => return Err(SessionError::NotFound),
+            Err(_) => return Err(SessionError::OtherError),
+        };
+
+        if redeemed.expires_at < chrono::Utc::now().timestamp() {
+            return Err(SessionError::Expired);
+        }
+
+        match sqlx::query_as!(
+            PlayerModel,
+            "SELECT * FROM players WHERE id = $1",
+            redeemed.player_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(player) => Ok(player),
+            Err(SqlxError::RowNotFound) => Err(SessionError::NotFound),
+            Err(_) => Err(SessionError::OtherError),
+        }
+    }
+
+    /// Deletes expired sessions and socket tickets, returning how many.
+    pub async fn purge_expired(&self) -> Result<u64, SessionError> {
+        let now = chrono::Utc::now().timestamp();
+        let sessions = match sqlx::query!("DELETE FROM sessions WHERE expires_at < $1", now)
+            .execute(&self.pool)
+            .await
+        {
+            Ok(res) => res.rows_affected(),
+            Err(_) => return Err(SessionError::OtherError),
+        };
+        let tickets = match sqlx::query!("DELETE FROM socket_tickets WHERE expires_at < $1", now)
+            .execute(&self.pool)
+            .await
+        {
+            Ok(res) => res.rows_affected(),
+            Err(_) => return Err(SessionError::OtherError),
+        };
+
+        Ok(sessions + tickets)
+    }
+}
+
+fn random_token() -> String {
+    let mut token_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut token_bytes);
+    hex::encode(token_bytes)
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
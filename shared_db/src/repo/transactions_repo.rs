@@ -1,11 +1,22 @@
-use sqlx::PgPool;
+use sqlx::{Error as SqlxError, PgConnection, PgExecutor, PgPool};
 use uuid::Uuid;
 
-use crate::model::{CreateMoneyTransactionModel, MoneyTransactionModel, NumericHandler};
+use crate::config::game_config;
+use crate::constants::{COMPACTION_BUCKET_SECS, MAX_HISTORY_PAGE_SIZE, MAX_STATS_BUCKETS};
+use crate::model::{
+    barrel_value, CashflowBucketModel, CreateMoneyTransactionModel, ItemType, LedgerEntryModel,
+    LedgerPageModel, Money, MoneyTransactionModel, PlatformReturnModel, SettlementModel,
+    TransactionFilterModel, TransactionKind,
+};
+
+use super::research_repo::research_effects;
 
 #[derive(Debug)]
 pub enum MoneyTransactionError {
     InvalidAmount,
+    Overflow,
+    InvalidFilter,
+    PlayerNotFound,
     OtherError,
 }
 
@@ -13,6 +24,11 @@ impl std::fmt::Display for MoneyTransactionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
             MoneyTransactionError::InvalidAmount => write!(f, "Invalid transaction amount"),
+            MoneyTransactionError::Overflow => write!(f, "Amount of money out of range"),
+            MoneyTransactionError::InvalidFilter => {
+                write!(f, "Invalid transaction history page or filter")
+            }
+            MoneyTransactionError::PlayerNotFound => write!(f, "Player not found"),
             MoneyTransactionError::OtherError => write!(f, "Other database-related error"),
         }
     }
@@ -29,82 +45,804 @@ impl TransactionsRepo {
         TransactionsRepo { pool }
     }
 
-    pub async fn get_available_balance(&self) -> Result<i64, MoneyTransactionError> {
-        let query_result =
-            match sqlx::query_as!(NumericHandler, "SELECT CAST(SUM(CASE WHEN reduces_balance = FALSE THEN amount ELSE -amount END) AS DECIMAL) AS calculation FROM money_transactions")
-                .fetch_one(&self.pool)
-                .await
-            {
-                Ok(balance) => balance,
-                Err(_) => return Err(MoneyTransactionError::OtherError),
-            };
-
-        // convert from BigDecimal to i64
-        let balance: Option<i64> = query_result
-            .calculation
-            .map(|bd| bd.to_string().parse().unwrap_or(0));
-        match balance {
-            Some(profit) => Ok(profit),
-            None => Err(MoneyTransactionError::OtherError),
-        }
+    pub async fn get_available_balance(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Money, MoneyTransactionError> {
+        available_balance(&self.pool, player_id).await
     }
 
-    pub async fn get_period_platform_earnings(&self) -> Result<i64, MoneyTransactionError> {
+    pub async fn get_by_id(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<MoneyTransactionModel, MoneyTransactionError> {
         let query_result = match sqlx::query_as!(
-            NumericHandler,
-            "SELECT SUM(profitability) AS calculation FROM oil_platforms",
+            MoneyTransactionModel,
+            "SELECT * FROM money_transactions WHERE id = $1 AND player_id = $2",
+            id,
+            player_id
         )
         .fetch_one(&self.pool)
         .await
         {
-            Ok(earnings) => earnings,
+            Ok(tx) => tx,
             Err(_) => return Err(MoneyTransactionError::OtherError),
         };
+        Ok(query_result)
+    }
 
-        // convert from BigDecimal to i64
-        let total_profit: Option<i64> = query_result
-            .calculation
-            .map(|bd| bd.to_string().parse().unwrap_or(0));
-        match total_profit {
-            Some(profit) => Ok(profit),
-            None => Err(MoneyTransactionError::OtherError),
-        }
-    }
-
-    pub async fn get_all(&self) -> Result<Vec<MoneyTransactionModel>, MoneyTransactionError> {
-        let query_result =
-            match sqlx::query_as!(MoneyTransactionModel, "SELECT * FROM money_transactions")
-                .fetch_all(&self.pool)
-                .await
-            {
-                Ok(platforms) => platforms,
-                Err(_) => return Err(MoneyTransactionError::OtherError),
-            };
+    pub async fn get_all(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Vec<MoneyTransactionModel>, MoneyTransactionError> {
+        let query_result = match sqlx::query_as!(
+            MoneyTransactionModel,
+            "SELECT * FROM money_transactions WHERE player_id = $1 ORDER BY created_at ASC, seq ASC",
+            player_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(platforms) => platforms,
+            Err(_) => return Err(MoneyTransactionError::OtherError),
+        };
         Ok(query_result)
     }
 
+    /// Returns one page of the player's ledger, newest first. The running
+    /// balance is computed over the whole ledger, not just the filtered rows.
+    pub async fn get_history(
+        &self,
+        player_id: Uuid,
+        filter: TransactionFilterModel,
+    ) -> Result<LedgerPageModel, MoneyTransactionError> {
+        if filter.page < 1 || filter.limit < 1 || filter.limit > MAX_HISTORY_PAGE_SIZE {
+            return Err(MoneyTransactionError::InvalidFilter);
+        }
+        let offset = match (filter.page - 1).checked_mul(filter.limit) {
+            Some(offset) => offset,
+            None => return Err(MoneyTransactionError::InvalidFilter),
+        };
+
+        let entries = match sqlx::query_as!(
+            LedgerEntryModel,
+            r#"SELECT id AS "id!", player_id AS "player_id!", item_id AS "item_id!", amount AS "amount!", reduces_balance AS "reduces_balance!", kind AS "kind!", item_type AS "item_type!", running_balance AS "running_balance!", created_at, updated_at
+            FROM (
+                SELECT *, CAST(SUM(CASE WHEN reduces_balance = FALSE THEN amount ELSE -amount END) OVER (ORDER BY created_at ASC, seq ASC) AS BIGINT) AS running_balance
+                FROM money_transactions WHERE player_id = $1
+            ) ledger
+            WHERE ($2::BIGINT IS NULL OR created_at >= $2)
+                AND ($3::BIGINT IS NULL OR created_at <= $3)
+                AND ($4::BOOLEAN IS NULL OR reduces_balance = $4)
+                AND ($5::UUID IS NULL OR item_id = $5)
+            ORDER BY created_at DESC, seq DESC
+            LIMIT $6 OFFSET $7"#,
+            player_id,
+            filter.from,
+            filter.to,
+            filter.reduces_balance,
+            filter.item_id,
+            filter.limit,
+            offset,
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(entries) => entries,
+            Err(e) => return Err(money_error(e)),
+        };
+
+        let total = match sqlx::query!(
+            r#"SELECT COUNT(*) AS "total!" FROM money_transactions
+            WHERE player_id = $1
+                AND ($2::BIGINT IS NULL OR created_at >= $2)
+                AND ($3::BIGINT IS NULL OR created_at <= $3)
+                AND ($4::BOOLEAN IS NULL OR reduces_balance = $4)
+                AND ($5::UUID IS NULL OR item_id = $5)"#,
+            player_id,
+            filter.from,
+            filter.to,
+            filter.reduces_balance,
+            filter.item_id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        {
+            Ok(row) => row.total,
+            Err(_) => return Err(MoneyTransactionError::OtherError),
+        };
+
+        Ok(LedgerPageModel { entries, total })
+    }
+
+    /// Sums income and spending per `bucket_secs` long bucket between `from`
+    /// and `to`, including empty buckets. Compacted earnings show up in the
+    /// bucket of the last entry they replaced.
+    pub async fn get_cashflow(
+        &self,
+        player_id: Uuid,
+        bucket_secs: i64,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<CashflowBucketModel>, MoneyTransactionError> {
+        if bucket_secs < 1 || to < from {
+            return Err(MoneyTransactionError::InvalidFilter);
+        }
+        // the last bucket ends at most `bucket_secs` after `to`
+        match (to.checked_sub(from), to.checked_add(bucket_secs)) {
+            (Some(span), Some(_)) if span / bucket_secs < MAX_STATS_BUCKETS => (),
+            _ => return Err(MoneyTransactionError::InvalidFilter),
+        }
+
+        match sqlx::query_as!(
+            CashflowBucketModel,
+            r#"SELECT b.bucket_start AS "bucket_start!",
+                CAST(COALESCE(SUM(t.amount) FILTER (WHERE t.reduces_balance = FALSE), 0) AS BIGINT) AS "income!",
+                CAST(COALESCE(SUM(t.amount) FILTER (WHERE t.reduces_balance = TRUE), 0) AS BIGINT) AS "spending!"
+            FROM generate_series($2::BIGINT / $4::BIGINT * $4, $3::BIGINT / $4 * $4, $4) AS b (bucket_start)
+            LEFT JOIN money_transactions t ON t.player_id = $1
+                AND t.created_at >= b.bucket_start AND t.created_at < b.bucket_start + $4
+            GROUP BY b.bucket_start
+            ORDER BY b.bucket_start ASC"#,
+            player_id,
+            from,
+            to,
+            bucket_secs,
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(buckets) => Ok(buckets),
+            Err(e) => Err(money_error(e)),
+        }
+    }
+
+    /// Compares the ledger investment (purchase and upgrades) of every
+    /// platform the player owns with its earnings and current income less
+    /// upkeep.
+    pub async fn get_platform_returns(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Vec<PlatformReturnModel>, MoneyTransactionError> {
+        let effects = match research_effects(&self.pool, player_id).await {
+            Ok(effects) => effects,
+            Err(e) => return Err(money_error(e)),
+        };
+
+        let rows = match sqlx::query!(
+            r#"SELECT p.id, p.platform_type, p.platform_level, p.profitability, p.condition, t.upkeep,
+                COALESCE((
+                    SELECT price FROM oil_prices ORDER BY recorded_at DESC LIMIT 1
+                ), $2) AS "oil_price!",
+                CAST(COALESCE((
+                    SELECT SUM(t.amount) FROM money_transactions t
+                    WHERE t.player_id = p.player_id AND t.item_id = p.id
+                        AND t.kind IN ('platform_purchase', 'platform_upgrade')
+                ), 0) AS BIGINT) AS "invested!: Money",
+                CAST(COALESCE((
+                    SELECT SUM(e.amount) FROM platform_earnings e
+                    WHERE e.player_id = p.player_id AND e.platform_id = p.id
+                ), 0) AS BIGINT) AS "earned!: Money"
+            FROM oil_platforms p
+            JOIN platform_types t ON t.name = p.platform_type
+            WHERE p.player_id = $1
+            ORDER BY p.created_at ASC"#,
+            player_id,
+            game_config().oil_market.base_price
+        )
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(e) => return Err(money_error(e)),
+        };
+
+        let returns = rows
+            .into_iter()
+            .map(|row| {
+                let invested = row.invested.as_i64();
+                // broken platforms neither produce nor cost upkeep
+                let (income, upkeep) = if row.condition > 0 {
+                    let barrel_price = row.oil_price * effects.profitability_multiplier;
+                    (barrel_value(row.profitability, barrel_price), row.upkeep)
+                } else {
+                    (0, 0)
+                };
+                let net_income = income.saturating_sub(upkeep);
+                PlatformReturnModel {
+                    platform_id: row.id,
+                    platform_type: row.platform_type,
+                    platform_level: row.platform_level,
+                    invested: row.invested,
+                    earned: row.earned,
+                    income_per_period: Money::from(income),
+                    upkeep_per_period: Money::from(upkeep),
+                    // rounded up, a partial period does not pay back anything
+                    payback_periods: (net_income > 0)
+                        .then(|| (invested + net_income - 1) / net_income),
+                }
+            })
+            .collect();
+        Ok(returns)
+    }
+
     pub async fn create(
         &self,
+        player_id: Uuid,
         item: CreateMoneyTransactionModel,
     ) -> Result<MoneyTransactionModel, MoneyTransactionError> {
-        if &item.amount < &0 {
-            return Err(MoneyTransactionError::InvalidAmount);
-        }
+        insert_transaction(&self.pool, player_id, item).await
+    }
 
-        let query_result = match sqlx::query_as!(
-            MoneyTransactionModel,
-            "INSERT INTO money_transactions (item_id, amount, reduces_balance) VALUES ($1, $2, $3) RETURNING *",
-            item.item_id.unwrap_or_else(|| Uuid::nil()),
-            item.amount,
-            item.reduces_balance,
+    /// Rolls the earnings and upkeep entries booked before `before` into one
+    /// entry of each per player and day, split wherever other entries were
+    /// booked in between. The summaries take the place of the last entry they
+    /// replace, so balances and the running balances of the history stay the
+    /// same. Returns by how many entries the ledger shrank.
+    pub async fn compact_earnings(&self, before: i64) -> Result<i64, MoneyTransactionError> {
+        let player_ids = match sqlx::query!(
+            r#"SELECT DISTINCT player_id FROM money_transactions
+            WHERE kind IN ('earnings', 'upkeep') AND created_at < $1
+            GROUP BY player_id, kind, created_at / $2
+            HAVING COUNT(*) > 1"#,
+            before,
+            COMPACTION_BUCKET_SECS,
         )
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
         .await
         {
+            Ok(rows) => rows.into_iter().map(|row| row.player_id),
+            Err(_) => return Err(MoneyTransactionError::OtherError),
+        };
+
+        let mut removed = 0;
+        for player_id in player_ids {
+            removed += self.compact_player_earnings(player_id, before).await?;
+        }
+        Ok(removed)
+    }
+
+    async fn compact_player_earnings(
+        &self,
+        player_id: Uuid,
+        before: i64,
+    ) -> Result<i64, MoneyTransactionError> {
+        let mut tx = match self.pool.begin().await {
             Ok(tx) => tx,
             Err(_) => return Err(MoneyTransactionError::OtherError),
         };
 
-        Ok(query_result)
+        // lock the player first, like settlements and purchases do, so that
+        // the balance snapshot is always locked in the same order
+        match sqlx::query!("SELECT id FROM players WHERE id = $1 FOR UPDATE", player_id)
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(_) => (),
+            Err(SqlxError::RowNotFound) => return Err(MoneyTransactionError::PlayerNotFound),
+            Err(_) => return Err(MoneyTransactionError::OtherError),
+        };
+
+        // the history is only rewritten, nothing happened that the websocket
+        // app would have to push
+        match sqlx::query!("SELECT set_config('app.quiet_changes', 'on', TRUE)")
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(_) => (),
+            Err(_) => return Err(MoneyTransactionError::OtherError),
+        };
+
+        let compacted = compact_runs(&mut tx, player_id, before).await?;
+
+        match tx.commit().await {
+            Ok(_) => Ok(compacted),
+            Err(_) => Err(MoneyTransactionError::OtherError),
+        }
+    }
+
+    /// Credits the platform earnings of every full period elapsed since the
+    /// player was last settled, as a single ledger entry, debits the upkeep
+    /// of the running platforms as another and wears them down.
+    pub async fn settle(&self, player_id: Uuid) -> Result<SettlementModel, MoneyTransactionError> {
+        self.settle_periods(player_id, None).await
+    }
+
+    /// Settles the progress made while the player had no game open, like
+    /// `settle`, but periods beyond the configured offline limit are
+    /// forfeited.
+    pub async fn settle_offline(
+        &self,
+        player_id: Uuid,
+    ) -> Result<SettlementModel, MoneyTransactionError> {
+        self.settle_periods(player_id, Some(game_config().max_offline_periods()))
+            .await
+    }
+
+    async fn settle_periods(
+        &self,
+        player_id: Uuid,
+        max_periods: Option<i64>,
+    ) -> Result<SettlementModel, MoneyTransactionError> {
+        let mut tx = match self.pool.begin().await {
+            Ok(tx) => tx,
+            Err(_) => return Err(MoneyTransactionError::OtherError),
+        };
+
+        let settlement = settle_pending(&mut tx, player_id, max_periods).await?;
+
+        match tx.commit().await {
+            Ok(_) => Ok(settlement),
+            Err(_) => Err(MoneyTransactionError::OtherError),
+        }
+    }
+}
+
+// settles the periods elapsed since the player was last settled, at most
+// `max_periods` of them; purchases settle first, so that the periods before
+// them are paid out at the platforms the player had back then
+pub(crate) async fn settle_pending(
+    conn: &mut PgConnection,
+    player_id: Uuid,
+    max_periods: Option<i64>,
+) -> Result<SettlementModel, MoneyTransactionError> {
+    // locking the player row keeps concurrent settlements from crediting
+    // the same periods twice
+    let last_settled_at = match sqlx::query!(
+        "SELECT last_settled_at FROM players WHERE id = $1 FOR UPDATE",
+        player_id
+    )
+    .fetch_one(&mut *conn)
+    .await
+    {
+        Ok(row) => row.last_settled_at,
+        Err(SqlxError::RowNotFound) => return Err(MoneyTransactionError::PlayerNotFound),
+        Err(_) => return Err(MoneyTransactionError::OtherError),
+    };
+
+    let period_secs = game_config().tick_period_secs;
+    let elapsed_periods = (chrono::Utc::now().timestamp() - last_settled_at) / period_secs;
+    if elapsed_periods <= 0 {
+        return Ok(SettlementModel {
+            periods: 0,
+            earned: Money::ZERO,
+            upkeep: Money::ZERO,
+        });
+    }
+    let periods = match max_periods {
+        Some(max_periods) => elapsed_periods.min(max_periods),
+        None => elapsed_periods,
+    };
+
+    // capped settlements credit the most recent periods
+    let first_period = last_settled_at + (elapsed_periods - periods) * period_secs;
+    let effects = match research_effects(&mut *conn, player_id).await {
+        Ok(effects) => effects,
+        Err(e) => return Err(money_error(e)),
+    };
+    let platform_earnings = settled_platform_earnings(
+        &mut *conn,
+        player_id,
+        first_period,
+        periods,
+        effects.profitability_multiplier,
+    )
+    .await?;
+    let earned = match Money::checked_sum(platform_earnings.iter().map(|row| row.earned)) {
+        Some(earned) => earned,
+        None => return Err(MoneyTransactionError::Overflow),
+    };
+    let upkeep_due = match Money::checked_sum(platform_earnings.iter().map(|row| row.upkeep)) {
+        Some(upkeep) => upkeep,
+        None => return Err(MoneyTransactionError::Overflow),
+    };
+    if earned > Money::ZERO {
+        let earnings_tx = CreateMoneyTransactionModel {
+            item_id: None,
+            amount: earned,
+            reduces_balance: false,
+            kind: TransactionKind::Earnings,
+            item_type: ItemType::System,
+        };
+        let created = insert_transaction(&mut *conn, player_id, earnings_tx).await?;
+
+        // attribute the lump sum to the platforms that earned it
+        let (platform_ids, amounts): (Vec<Uuid>, Vec<i64>) = platform_earnings
+            .into_iter()
+            .filter(|row| row.earned > Money::ZERO)
+            .map(|row| (row.platform_id, row.earned.as_i64()))
+            .unzip();
+        if let Err(e) = sqlx::query!(
+            "INSERT INTO platform_earnings (transaction_id, player_id, platform_id, amount) SELECT $1, $2, * FROM UNNEST($3::UUID[], $4::BIGINT[])",
+            created.id,
+            player_id,
+            &platform_ids,
+            &amounts
+        )
+        .execute(&mut *conn)
+        .await
+        {
+            return Err(money_error(e));
+        }
+    }
+
+    // upkeep the player cannot pay is waived, balances do not go negative
+    let balance = available_balance(&mut *conn, player_id).await?;
+    let upkeep = upkeep_due.min(balance.max(Money::ZERO));
+    if upkeep > Money::ZERO {
+        let upkeep_tx = CreateMoneyTransactionModel {
+            item_id: None,
+            amount: upkeep,
+            reduces_balance: true,
+            kind: TransactionKind::Upkeep,
+            item_type: ItemType::System,
+        };
+        insert_transaction(&mut *conn, player_id, upkeep_tx).await?;
+    }
+
+    // running platforms wear down over the settled periods
+    let wear = i64::from(game_config().maintenance.wear_per_tick) * periods;
+    if wear > 0 {
+        if let Err(e) = sqlx::query!(
+            "UPDATE oil_platforms SET condition = GREATEST(condition - $1::BIGINT, 0) WHERE player_id = $2 AND condition > 0",
+            wear,
+            player_id
+        )
+        .execute(&mut *conn)
+        .await
+        {
+            return Err(money_error(e));
+        }
+    }
+
+    // only whole periods are settled, the remainder counts towards the next one
+    if sqlx::query!(
+        "UPDATE players SET last_settled_at = $1 WHERE id = $2",
+        last_settled_at + elapsed_periods * period_secs,
+        player_id
+    )
+    .execute(&mut *conn)
+    .await
+    .is_err()
+    {
+        return Err(MoneyTransactionError::OtherError);
+    }
+
+    Ok(SettlementModel {
+        periods,
+        earned,
+        upkeep,
+    })
+}
+
+// executor-generic queries, shared with the service layer so that they can
+// also run inside a database transaction
+pub(crate) async fn available_balance<'e, E: PgExecutor<'e>>(
+    executor: E,
+    player_id: Uuid,
+) -> Result<Money, MoneyTransactionError> {
+    // the snapshot is kept in step with the ledger by a database trigger,
+    // players without any ledger entries have nothing
+    match sqlx::query_scalar!(
+        r#"SELECT balance AS "balance: Money" FROM player_balances WHERE player_id = $1"#,
+        player_id
+    )
+    .fetch_optional(executor)
+    .await
+    {
+        Ok(balance) => Ok(balance.unwrap_or(Money::ZERO)),
+        Err(e) => Err(money_error(e)),
+    }
+}
+
+// a ledger entry as compaction sees it
+struct CompactionEntry {
+    id: Uuid,
+    kind: TransactionKind,
+    created_at: i64,
+    seq: i64,
+}
+
+// entries rolled into one summary, which reuses the time and sequence number
+// of the last of them and so keeps its place in the history
+#[derive(Debug, PartialEq)]
+struct CompactionGroup {
+    ids: Vec<Uuid>,
+    created_at: i64,
+    seq: i64,
+}
+
+// groups the entries of `kind` by run and day, where a run is broken by every
+// entry that is neither earnings nor upkeep; `entries` are in ledger order and
+// only groups of more than one entry are returned
+fn compaction_groups(
+    entries: &[CompactionEntry],
+    kind: TransactionKind,
+    bucket_secs: i64,
+) -> Vec<CompactionGroup> {
+    let mut groups: Vec<CompactionGroup> = Vec::new();
+    let mut run = 0;
+    let mut current = None;
+
+    for entry in entries {
+        if !matches!(
+            entry.kind,
+            TransactionKind::Earnings | TransactionKind::Upkeep
+        ) {
+            run += 1;
+            continue;
+        }
+        if entry.kind != kind {
+            continue;
+        }
+
+        let key = (run, entry.created_at.div_euclid(bucket_secs));
+        match groups.last_mut() {
+            Some(group) if current == Some(key) => {
+                group.ids.push(entry.id);
+                group.created_at = entry.created_at;
+                group.seq = entry.seq;
+            }
+            _ => {
+                groups.push(CompactionGroup {
+                    ids: vec![entry.id],
+                    created_at: entry.created_at,
+                    seq: entry.seq,
+                });
+                current = Some(key);
+            }
+        }
+    }
+
+    groups.retain(|group| group.ids.len() > 1);
+    groups
+}
+
+// rolls the earnings and upkeep entries booked before `before` into one entry
+// of each per run and day; returns by how many entries the ledger shrank
+async fn compact_runs(
+    conn: &mut PgConnection,
+    player_id: Uuid,
+    before: i64,
+) -> Result<i64, MoneyTransactionError> {
+    let entries = match sqlx::query_as!(
+        CompactionEntry,
+        r#"SELECT id, kind, created_at AS "created_at!", seq FROM money_transactions
+        WHERE player_id = $1 AND created_at < $2
+        ORDER BY created_at ASC, seq ASC"#,
+        player_id,
+        before,
+    )
+    .fetch_all(&mut *conn)
+    .await
+    {
+        Ok(entries) => entries,
+        Err(e) => return Err(money_error(e)),
+    };
+
+    let (mut ids, mut created_ats, mut seqs) = (Vec::new(), Vec::new(), Vec::new());
+    for kind in [TransactionKind::Earnings, TransactionKind::Upkeep] {
+        for group in compaction_groups(&entries, kind, COMPACTION_BUCKET_SECS) {
+            for id in group.ids {
+                ids.push(id);
+                created_ats.push(group.created_at);
+                seqs.push(group.seq);
+            }
+        }
+    }
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    match sqlx::query!(
+        r#"WITH grouped AS (
+            SELECT * FROM UNNEST($2::UUID[], $3::BIGINT[], $4::BIGINT[]) AS g (id, created_at, seq)
+        ), removed AS (
+            DELETE FROM money_transactions t
+            USING grouped g
+            WHERE t.id = g.id AND t.player_id = $1
+            RETURNING t.id, t.amount, t.reduces_balance, t.kind, g.created_at, g.seq
+        ), inserted AS (
+            INSERT INTO money_transactions (player_id, item_id, amount, reduces_balance, kind, item_type, created_at, updated_at, seq)
+            SELECT $1, $5, SUM(amount), BOOL_AND(reduces_balance), kind, 'system', created_at, $6, seq
+            FROM removed
+            GROUP BY kind, created_at, seq
+            RETURNING id, created_at, seq
+        ), attributed AS (
+            -- the attribution of the removed entries cascades away, the
+            -- summary entries take it over per platform
+            INSERT INTO platform_earnings (transaction_id, player_id, platform_id, amount, created_at)
+            SELECT i.id, $1, e.platform_id, SUM(e.amount), i.created_at
+            FROM platform_earnings e
+            JOIN removed r ON r.id = e.transaction_id
+            JOIN inserted i ON i.seq = r.seq
+            GROUP BY i.id, i.created_at, e.platform_id
+        )
+        SELECT (SELECT COUNT(*) FROM removed) AS "removed!", (SELECT COUNT(*) FROM inserted) AS "inserted!""#,
+        player_id,
+        &ids,
+        &created_ats,
+        &seqs,
+        Uuid::nil(),
+        chrono::Utc::now().timestamp(),
+    )
+    .fetch_one(&mut *conn)
+    .await
+    {
+        Ok(row) => Ok(row.removed - row.inserted),
+        Err(e) => Err(money_error(e)),
+    }
+}
+
+// what a platform earned and cost over the settled periods
+struct SettledPlatform {
+    platform_id: Uuid,
+    earned: Money,
+    upkeep: Money,
+}
+
+// what each platform of the player earned in `periods` periods starting at
+// `first_period`, every period sold at the oil price times the research
+// multiplier and scaled by the market event of its start
+async fn settled_platform_earnings<'e, E: PgExecutor<'e>>(
+    executor: E,
+    player_id: Uuid,
+    first_period: i64,
+    periods: i64,
+    profitability_multiplier: f64,
+) -> Result<Vec<SettledPlatform>, MoneyTransactionError> {
+    // a platform runs in a period unless it has worn down to zero by then
+    match sqlx::query_as!(
+        SettledPlatform,
+        r#"SELECT p.id AS platform_id,
+            CAST(SUM(CASE WHEN p.condition - $6 * k > 0
+                THEN FLOOR(p.profitability * (COALESCE(o.price, $5) * $7) * COALESCE(e.multiplier, 1))
+                ELSE 0
+            END) AS BIGINT) AS "earned!: Money",
+            CAST(SUM(CASE WHEN p.condition - $6 * k > 0 THEN t.upkeep ELSE 0 END) AS BIGINT) AS "upkeep!: Money"
+        FROM oil_platforms p
+        JOIN platform_types t ON t.name = p.platform_type
+        CROSS JOIN generate_series(0, $3::BIGINT - 1) AS k
+        LEFT JOIN LATERAL (
+            SELECT price FROM oil_prices
+            WHERE recorded_at <= $2::BIGINT + k * $4::BIGINT
+            ORDER BY recorded_at DESC
+            LIMIT 1
+        ) o ON TRUE
+        LEFT JOIN LATERAL (
+            SELECT multiplier FROM events
+            WHERE starts_at <= $2::BIGINT + k * $4::BIGINT AND ends_at > $2::BIGINT + k * $4::BIGINT
+                AND (platform_type IS NULL OR platform_type = p.platform_type)
+            ORDER BY starts_at DESC
+            LIMIT 1
+        ) e ON TRUE
+        WHERE p.player_id = $1
+        GROUP BY p.id"#,
+        player_id,
+        first_period,
+        periods,
+        game_config().tick_period_secs,
+        game_config().oil_market.base_price,
+        i64::from(game_config().maintenance.wear_per_tick),
+        profitability_multiplier
+    )
+    .fetch_all(executor)
+    .await
+    {
+        Ok(rows) => Ok(rows),
+        Err(e) => Err(money_error(e)),
+    }
+}
+
+pub(crate) async fn insert_transaction<'e, E: PgExecutor<'e>>(
+    executor: E,
+    player_id: Uuid,
+    item: CreateMoneyTransactionModel,
+) -> Result<MoneyTransactionModel, MoneyTransactionError> {
+    if item.amount.is_negative() {
+        return Err(MoneyTransactionError::InvalidAmount);
+    }
+
+    let query_result = match sqlx::query_as!(
+        MoneyTransactionModel,
+        "INSERT INTO money_transactions (player_id, item_id, amount, reduces_balance, kind, item_type) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+        player_id,
+        item.item_id.unwrap_or_else(Uuid::nil),
+        item.amount.as_i64(),
+        item.reduces_balance,
+        item.kind.as_str(),
+        item.item_type.as_str(),
+    )
+    .fetch_one(executor)
+    .await
+    {
+        Ok(tx) => tx,
+        Err(e) => return Err(money_error(e)),
+    };
+
+    Ok(query_result)
+}
+
+// sums and balances that do not fit into a BIGINT fail with
+// numeric_value_out_of_range instead of wrapping
+pub(crate) fn money_error(e: SqlxError) -> MoneyTransactionError {
+    match e {
+        SqlxError::Database(e) if e.code().as_deref() == Some("22003") => {
+            MoneyTransactionError::Overflow
+        }
+        _ => MoneyTransactionError::OtherError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_SECS: i64 = 24 * 60 * 60;
+
+    fn ledger(entries: &[(TransactionKind, i64)]) -> Vec<CompactionEntry> {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(seq, &(kind, created_at))| CompactionEntry {
+                id: Uuid::from_u128(seq as u128),
+                kind,
+                created_at,
+                seq: seq as i64,
+            })
+            .collect()
+    }
+
+    fn group(seqs: &[u128], created_at: i64) -> CompactionGroup {
+        CompactionGroup {
+            ids: seqs.iter().map(|&seq| Uuid::from_u128(seq)).collect(),
+            created_at,
+            seq: *seqs.last().unwrap() as i64,
+        }
+    }
+
+    #[test]
+    fn compaction_groups_are_broken_by_other_entries_and_days() {
+        let entries = ledger(&[
+            (TransactionKind::StartingBalance, 0),
+            (TransactionKind::Earnings, 10),
+            (TransactionKind::Upkeep, 10),
+            (TransactionKind::Earnings, 20),
+            (TransactionKind::Upkeep, 20),
+            (TransactionKind::PlatformPurchase, 20),
+            (TransactionKind::Earnings, 30),
+            (TransactionKind::Earnings, 40),
+            (TransactionKind::Earnings, DAY_SECS),
+            (TransactionKind::Earnings, DAY_SECS + 10),
+            (TransactionKind::Upkeep, DAY_SECS + 10),
+        ]);
+
+        assert_eq!(
+            compaction_groups(&entries, TransactionKind::Earnings, DAY_SECS),
+            vec![
+                group(&[1, 3], 20),
+                group(&[6, 7], 40),
+                group(&[8, 9], DAY_SECS + 10),
+            ]
+        );
+        // upkeep runs interleave with the earnings, the last one is alone
+        assert_eq!(
+            compaction_groups(&entries, TransactionKind::Upkeep, DAY_SECS),
+            vec![group(&[2, 4], 20)]
+        );
+    }
+
+    #[test]
+    fn compaction_groups_skip_single_entries() {
+        let entries = ledger(&[
+            (TransactionKind::Earnings, 10),
+            (TransactionKind::BeerPurchase, 10),
+            (TransactionKind::Earnings, 20),
+            (TransactionKind::Research, 20),
+            (TransactionKind::Earnings, 30),
+        ]);
+
+        assert!(compaction_groups(&entries, TransactionKind::Earnings, DAY_SECS).is_empty());
     }
 }
@@ -0,0 +1,55 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::game_config;
+use crate::model::AchievementModel;
+use crate::repo::achievement_repo::{AchievementError, AchievementRepo};
+
+/// Unlocks the achievements configured in the `achievements` section of the
+/// game config once their rules are met.
+pub struct AchievementService {
+    achievement_repo: AchievementRepo,
+}
+
+impl AchievementService {
+    pub fn new(pool: PgPool) -> Self {
+        AchievementService {
+            achievement_repo: AchievementRepo::new(pool),
+        }
+    }
+
+    /// Checks the rules of the achievements the player has not unlocked yet,
+    /// with time limits judged as of `at`. Run after changes to the player's
+    /// game, returns what it unlocked.
+    pub async fn evaluate(
+        &self,
+        player_id: Uuid,
+        at: i64,
+    ) -> Result<Vec<AchievementModel>, AchievementError> {
+        let locked: Vec<String> = self
+            .achievement_repo
+            .get_all(player_id)
+            .await?
+            .into_iter()
+            .filter(|achievement| achievement.unlocked_at.is_none())
+            .map(|achievement| achievement.key)
+            .collect();
+        if locked.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let progress = self.achievement_repo.get_progress(player_id, at).await?;
+        let met: Vec<String> = game_config()
+            .achievements
+            .iter()
+            .filter(|achievement| locked.contains(&achievement.key))
+            .filter(|achievement| achievement.is_met(&progress))
+            .map(|achievement| achievement.key.clone())
+            .collect();
+        if met.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.achievement_repo.unlock(player_id, &met).await
+    }
+}
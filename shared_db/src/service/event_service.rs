@@ -0,0 +1,69 @@
+use rand::{distributions::WeightedIndex, prelude::*, rngs::StdRng};
+use sqlx::PgPool;
+
+use crate::config::game_config;
+use crate::model::{CreateMarketEventModel, MarketEventModel};
+use crate::repo::event_repo::{MarketEventError, MarketEventRepo};
+use crate::repo::platform_type_repo::PlatformTypeRepo;
+
+/// Starts random market events, one at a time, as configured in the
+/// `market_events` section of the game config.
+pub struct MarketEventService {
+    event_repo: MarketEventRepo,
+    platform_type_repo: PlatformTypeRepo,
+    rng: StdRng,
+}
+
+impl MarketEventService {
+    pub fn new(pool: PgPool) -> Self {
+        let rng = match game_config().market_events.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        MarketEventService {
+            event_repo: MarketEventRepo::new(pool.clone()),
+            platform_type_repo: PlatformTypeRepo::new(pool),
+            rng,
+        }
+    }
+
+    /// Rolls for a new event unless one is still running. Returns the event
+    /// that started, if any.
+    pub async fn roll(&mut self, now: i64) -> Result<Option<MarketEventModel>, MarketEventError> {
+        let config = &game_config().market_events;
+        if !self.event_repo.get_active(now).await?.is_empty() {
+            return Ok(None);
+        }
+        if !self.rng.gen_bool(config.chance_per_tick) {
+            return Ok(None);
+        }
+
+        let weights = config.kinds.iter().map(|kind| kind.weight);
+        let kind = match WeightedIndex::new(weights) {
+            Ok(index) => &config.kinds[index.sample(&mut self.rng)],
+            Err(_) => return Ok(None),
+        };
+
+        let platform_type = if kind.single_type {
+            let platform_types = match self.platform_type_repo.get_all().await {
+                Ok(platform_types) => platform_types,
+                Err(_) => return Err(MarketEventError::OtherError),
+            };
+            match platform_types.choose(&mut self.rng) {
+                Some(platform_type) => Some(platform_type.name.clone()),
+                None => return Ok(None),
+            }
+        } else {
+            None
+        };
+
+        let event = CreateMarketEventModel {
+            kind: kind.kind,
+            platform_type,
+            multiplier: kind.multiplier,
+            starts_at: now,
+            ends_at: now + kind.duration_secs,
+        };
+        self.event_repo.create(event).await.map(Some)
+    }
+}
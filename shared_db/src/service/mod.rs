@@ -0,0 +1,4 @@
+pub mod achievement_service;
+pub mod event_service;
+pub mod oil_market_service;
+pub mod purchase_service;
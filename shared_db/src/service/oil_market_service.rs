@@ -0,0 +1,54 @@
+use rand::{prelude::*, rngs::StdRng};
+use sqlx::PgPool;
+
+use crate::config::game_config;
+use crate::model::OilPriceModel;
+use crate::repo::oil_price_repo::{OilPriceError, OilPriceRepo};
+
+/// Moves the oil price once per tick as configured in the `oil_market`
+/// section of the game config, continuing from the last recorded price.
+pub struct OilMarketService {
+    oil_price_repo: OilPriceRepo,
+    rng: StdRng,
+    price: Option<f64>,
+}
+
+impl OilMarketService {
+    pub fn new(pool: PgPool) -> Self {
+        let rng = match game_config().oil_market.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        OilMarketService {
+            oil_price_repo: OilPriceRepo::new(pool),
+            rng,
+            price: None,
+        }
+    }
+
+    /// Records the price for the tick at `now`.
+    pub async fn step(&mut self, now: i64) -> Result<OilPriceModel, OilPriceError> {
+        let config = &game_config().oil_market;
+        let current = match self.price {
+            Some(price) => price,
+            None => match self.oil_price_repo.get_latest().await? {
+                Some(latest) => latest.price,
+                None => config.base_price,
+            },
+        };
+
+        let noise = self.rng.gen_range(-1.0..=1.0) * config.volatility * config.base_price;
+        let next = (current + config.reversion * (config.base_price - current) + noise)
+            .clamp(config.min_price, config.max_price);
+
+        let recorded = self
+            .oil_price_repo
+            .create(OilPriceModel {
+                recorded_at: now,
+                price: next,
+            })
+            .await?;
+        self.price = Some(recorded.price);
+        Ok(recorded)
+    }
+}
@@ -0,0 +1,636 @@
+use sqlx::{Error as SqlxError, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::config::game_config;
+use crate::constants::{MAX_BULK_PURCHASE, MAX_CONDITION};
+use crate::model::{
+    BeerModel, BeerPurchaseModel, BulkPurchaseModel, BulkUpgradeModel, CreateMoneyTransactionModel,
+    ItemType, Money, OilPlatformModel, PlatformRepairModel, PlatformSaleModel, PlatformTypeModel,
+    TechnologyModel, TransactionKind, UpgradeQuoteModel,
+};
+use crate::repo::leaderboard_repo::record_completed_game;
+use crate::repo::research_repo::{fetch_technologies, locked_platform_types, research_effects};
+use crate::repo::transactions_repo::{
+    available_balance, insert_transaction, money_error, settle_pending, MoneyTransactionError,
+};
+
+#[derive(Debug)]
+pub enum PurchaseError {
+    NotFound,
+    UnknownPlatformType,
+    NotResearched,
+    MissingPrerequisites,
+    InsufficientFunds,
+    MaxLevelReached,
+    AlreadyPurchased,
+    NothingToRepair,
+    InvalidQuantity,
+    Overflow,
+    OtherError,
+}
+
+impl std::fmt::Display for PurchaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PurchaseError::NotFound => write!(f, "Purchased item not found"),
+            PurchaseError::UnknownPlatformType => write!(f, "Invalid platform type specified"),
+            PurchaseError::NotResearched => {
+                write!(f, "Platform type has not been researched yet")
+            }
+            PurchaseError::MissingPrerequisites => {
+                write!(f, "Research the prerequisites first")
+            }
+            PurchaseError::InsufficientFunds => write!(f, "Not enough funds for purchase"),
+            PurchaseError::MaxLevelReached => {
+                write!(f, "Maximum upgrade level of platform reached")
+            }
+            PurchaseError::AlreadyPurchased => write!(f, "Item already purchased"),
+            PurchaseError::NothingToRepair => write!(f, "Platform is in full condition"),
+            PurchaseError::InvalidQuantity => write!(f, "Invalid quantity requested"),
+            PurchaseError::Overflow => write!(f, "Amount of money out of range"),
+            PurchaseError::OtherError => write!(f, "Other database-related error"),
+        }
+    }
+}
+
+impl std::error::Error for PurchaseError {}
+
+/// Runs purchase workflows (balance check, entity mutation and ledger
+/// insert) inside a single database transaction.
+pub struct PurchaseService {
+    pool: PgPool,
+}
+
+impl PurchaseService {
+    pub fn new(pool: PgPool) -> Self {
+        PurchaseService { pool }
+    }
+
+    /// Buys a new platform of the given type at the type's current cost.
+    pub async fn purchase_platform(
+        &self,
+        player_id: Uuid,
+        platform_type: &str,
+    ) -> Result<OilPlatformModel, PurchaseError> {
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let retrieved_type = find_platform_type(&mut tx, platform_type).await?;
+        check_unlocked(&mut tx, player_id, &retrieved_type).await?;
+        check_balance(&mut tx, player_id, Money::from(retrieved_type.cost)).await?;
+        let created = insert_platform(&mut tx, player_id, &retrieved_type).await?;
+
+        commit(tx).await?;
+        Ok(created)
+    }
+
+    /// Buys up to `count` platforms of the given type, as many as the
+    /// balance covers. Fails if not even one is affordable.
+    pub async fn purchase_platforms(
+        &self,
+        player_id: Uuid,
+        platform_type: &str,
+        count: i64,
+    ) -> Result<BulkPurchaseModel, PurchaseError> {
+        if !(1..=MAX_BULK_PURCHASE).contains(&count) {
+            return Err(PurchaseError::InvalidQuantity);
+        }
+
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let retrieved_type = find_platform_type(&mut tx, platform_type).await?;
+        check_unlocked(&mut tx, player_id, &retrieved_type).await?;
+        let cost = Money::from(retrieved_type.cost);
+        let mut balance = available_balance(&mut *tx, player_id).await?;
+
+        let mut platforms = Vec::new();
+        let mut total_cost = Money::ZERO;
+        while (platforms.len() as i64) < count && cost <= balance {
+            platforms.push(insert_platform(&mut tx, player_id, &retrieved_type).await?);
+            balance = balance.checked_sub(cost).ok_or(PurchaseError::Overflow)?;
+            total_cost = total_cost
+                .checked_add(cost)
+                .ok_or(PurchaseError::Overflow)?;
+        }
+        if platforms.is_empty() {
+            return Err(PurchaseError::InsufficientFunds);
+        }
+
+        commit(tx).await?;
+        Ok(BulkPurchaseModel {
+            platforms,
+            requested: count,
+            total_cost,
+        })
+    }
+
+    /// Raises a platform by one level at the cost and profitability gain the
+    /// configured upgrade curves give for its current level.
+    pub async fn upgrade_platform(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<OilPlatformModel, PurchaseError> {
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let retrieved = find_platform(&mut tx, player_id, id).await?;
+        let retrieved_type = find_platform_type(&mut tx, &retrieved.platform_type).await?;
+        if retrieved.platform_level >= max_level(&mut tx, player_id, &retrieved_type).await? {
+            return Err(PurchaseError::MaxLevelReached);
+        }
+
+        let quote = upgrade_quote(&retrieved, &retrieved_type)?;
+        check_balance(&mut tx, player_id, quote.cost).await?;
+        let updated = apply_upgrade(&mut tx, player_id, &retrieved, &quote).await?;
+
+        commit(tx).await?;
+        Ok(updated)
+    }
+
+    /// Raises a platform by up to `levels` levels, or up to its maximum
+    /// level if `None`, as far as the balance covers. Fails if not even one
+    /// level is affordable.
+    pub async fn upgrade_platform_levels(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+        levels: Option<i64>,
+    ) -> Result<BulkUpgradeModel, PurchaseError> {
+        if levels.is_some_and(|levels| levels < 1) {
+            return Err(PurchaseError::InvalidQuantity);
+        }
+
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let mut platform = find_platform(&mut tx, player_id, id).await?;
+        let retrieved_type = find_platform_type(&mut tx, &platform.platform_type).await?;
+        let max_level = max_level(&mut tx, player_id, &retrieved_type).await?;
+        let remaining = i64::from(max_level) - i64::from(platform.platform_level);
+        if remaining <= 0 {
+            return Err(PurchaseError::MaxLevelReached);
+        }
+        let requested = match levels {
+            Some(levels) => levels.min(remaining),
+            None => remaining,
+        };
+        let mut balance = available_balance(&mut *tx, player_id).await?;
+
+        let mut upgraded = 0;
+        let mut total_cost = Money::ZERO;
+        while upgraded < requested {
+            let quote = upgrade_quote(&platform, &retrieved_type)?;
+            if quote.cost > balance {
+                break;
+            }
+            platform = apply_upgrade(&mut tx, player_id, &platform, &quote).await?;
+            balance = balance
+                .checked_sub(quote.cost)
+                .ok_or(PurchaseError::Overflow)?;
+            total_cost = total_cost
+                .checked_add(quote.cost)
+                .ok_or(PurchaseError::Overflow)?;
+            upgraded += 1;
+        }
+        if upgraded == 0 {
+            return Err(PurchaseError::InsufficientFunds);
+        }
+
+        commit(tx).await?;
+        Ok(BulkUpgradeModel {
+            platform,
+            requested,
+            levels: upgraded,
+            total_cost,
+        })
+    }
+
+    /// Restores a platform to full condition at the configured share of its
+    /// type's cost, proportional to the wear.
+    pub async fn repair_platform(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<PlatformRepairModel, PurchaseError> {
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let retrieved = find_platform(&mut tx, player_id, id).await?;
+        if retrieved.condition >= MAX_CONDITION {
+            return Err(PurchaseError::NothingToRepair);
+        }
+        let retrieved_type = find_platform_type(&mut tx, &retrieved.platform_type).await?;
+        let cost = game_config().repair_cost(retrieved_type.cost, retrieved.condition);
+        check_balance(&mut tx, player_id, cost).await?;
+
+        let repaired = match sqlx::query_as!(
+            OilPlatformModel,
+            "UPDATE oil_platforms SET condition = $1, updated_at = $2 WHERE id = $3 RETURNING *",
+            MAX_CONDITION,
+            chrono::Utc::now().timestamp(),
+            retrieved.id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(platform) => platform,
+            Err(_) => return Err(PurchaseError::OtherError),
+        };
+
+        if cost > Money::ZERO {
+            let platform_repair_tx = CreateMoneyTransactionModel {
+                item_id: Some(repaired.id),
+                amount: cost,
+                reduces_balance: true,
+                kind: TransactionKind::PlatformRepair,
+                item_type: ItemType::Platform,
+            };
+            insert_transaction(&mut *tx, player_id, platform_repair_tx).await?;
+        }
+
+        commit(tx).await?;
+        Ok(PlatformRepairModel {
+            platform: repaired,
+            cost,
+        })
+    }
+
+    /// Demolishes a platform, refunding the configured share of everything
+    /// spent on it according to the ledger.
+    pub async fn sell_platform(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<PlatformSaleModel, PurchaseError> {
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let deleted = match sqlx::query_as!(
+            OilPlatformModel,
+            "DELETE FROM oil_platforms WHERE id = $1 AND player_id = $2 RETURNING *",
+            id,
+            player_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(platform) => platform,
+            Err(SqlxError::RowNotFound) => return Err(PurchaseError::NotFound),
+            Err(_) => return Err(PurchaseError::OtherError),
+        };
+
+        // purchase and upgrades are debited with the platform as item
+        let invested = match sqlx::query_scalar!(
+            r#"SELECT CAST(COALESCE(SUM(amount), 0) AS BIGINT) AS "invested!: Money" FROM money_transactions WHERE player_id = $1 AND item_id = $2 AND kind IN ('platform_purchase', 'platform_upgrade')"#,
+            player_id,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(invested) => invested,
+            Err(e) => return Err(money_error(e).into()),
+        };
+        let refund = invested.share(game_config().sell_refund_fraction);
+
+        if refund > Money::ZERO {
+            let platform_refund_tx = CreateMoneyTransactionModel {
+                item_id: Some(deleted.id),
+                amount: refund,
+                reduces_balance: false,
+                kind: TransactionKind::Refund,
+                item_type: ItemType::Platform,
+            };
+            insert_transaction(&mut *tx, player_id, platform_refund_tx).await?;
+        }
+
+        commit(tx).await?;
+        Ok(PlatformSaleModel {
+            platform: deleted,
+            refund,
+        })
+    }
+
+    pub async fn purchase_beer(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<BeerModel, PurchaseError> {
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let retrieved = match sqlx::query_as!(
+            BeerModel,
+            r#"SELECT beers.id, title, description, thumbnail, cost,
+                EXISTS (SELECT 1 FROM beer_purchases WHERE beer_id = beers.id AND player_id = $2) AS purchased,
+                created_at, updated_at
+            FROM beers WHERE id = $1"#,
+            id,
+            player_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(beer) => beer,
+            Err(SqlxError::RowNotFound) => return Err(PurchaseError::NotFound),
+            Err(_) => return Err(PurchaseError::OtherError),
+        };
+
+        if retrieved.purchased == Some(true) {
+            return Err(PurchaseError::AlreadyPurchased);
+        }
+
+        let cost = Money::from(retrieved.cost);
+
+        check_balance(&mut tx, player_id, cost).await?;
+
+        match sqlx::query_as!(
+            BeerPurchaseModel,
+            "INSERT INTO beer_purchases (player_id, beer_id) VALUES ($1, $2) RETURNING *",
+            player_id,
+            id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(_) => (),
+            Err(_) => return Err(PurchaseError::OtherError),
+        };
+
+        let beer_purchase_tx = CreateMoneyTransactionModel {
+            item_id: Some(retrieved.id),
+            amount: cost,
+            reduces_balance: true,
+            kind: TransactionKind::BeerPurchase,
+            item_type: ItemType::Beer,
+        };
+        insert_transaction(&mut *tx, player_id, beer_purchase_tx).await?;
+
+        // the last beer wins the game, record it for the leaderboard
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = record_completed_game(&mut *tx, player_id, now).await {
+            return Err(money_error(e).into());
+        }
+
+        commit(tx).await?;
+        Ok(BeerModel {
+            purchased: Some(true),
+            ..retrieved
+        })
+    }
+
+    /// Researches a technology once its prerequisites are researched.
+    pub async fn purchase_research(
+        &self,
+        player_id: Uuid,
+        id: Uuid,
+    ) -> Result<TechnologyModel, PurchaseError> {
+        let mut tx = self.begin().await?;
+        lock_player(&mut tx, player_id).await?;
+
+        let retrieved = match fetch_technologies(&mut *tx, player_id, Some(id)).await {
+            Ok(mut technologies) => technologies.pop().ok_or(PurchaseError::NotFound)?,
+            Err(_) => return Err(PurchaseError::OtherError),
+        };
+        if retrieved.researched {
+            return Err(PurchaseError::AlreadyPurchased);
+        }
+        if !retrieved.available {
+            return Err(PurchaseError::MissingPrerequisites);
+        }
+
+        let cost = Money::from(retrieved.cost);
+        check_balance(&mut tx, player_id, cost).await?;
+
+        match sqlx::query!(
+            "INSERT INTO player_research (player_id, technology_id) VALUES ($1, $2)",
+            player_id,
+            id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(_) => (),
+            Err(_) => return Err(PurchaseError::OtherError),
+        };
+
+        let research_tx = CreateMoneyTransactionModel {
+            item_id: Some(retrieved.id),
+            amount: cost,
+            reduces_balance: true,
+            kind: TransactionKind::Research,
+            item_type: ItemType::Technology,
+        };
+        insert_transaction(&mut *tx, player_id, research_tx).await?;
+
+        commit(tx).await?;
+        Ok(TechnologyModel {
+            researched: true,
+            ..retrieved
+        })
+    }
+
+    async fn begin(&self) -> Result<Transaction<'static, Postgres>, PurchaseError> {
+        self.pool
+            .begin()
+            .await
+            .map_err(|_| PurchaseError::OtherError)
+    }
+}
+
+// locking the player row serializes all purchases of one player, so
+// concurrent requests cannot overdraw the balance; the periods elapsed since
+// the last settlement are settled before anything changes, so that platforms
+// bought while the player was away do not earn for the time before
+async fn lock_player(
+    tx: &mut Transaction<'static, Postgres>,
+    player_id: Uuid,
+) -> Result<(), PurchaseError> {
+    match sqlx::query!("SELECT id FROM players WHERE id = $1 FOR UPDATE", player_id)
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(_) => (),
+        Err(SqlxError::RowNotFound) => return Err(PurchaseError::NotFound),
+        Err(_) => return Err(PurchaseError::OtherError),
+    };
+
+    // without an open game the player is away, like on reconnecting
+    settle_pending(tx, player_id, Some(game_config().max_offline_periods())).await?;
+    Ok(())
+}
+
+async fn check_balance(
+    tx: &mut Transaction<'static, Postgres>,
+    player_id: Uuid,
+    cost: Money,
+) -> Result<(), PurchaseError> {
+    let balance = available_balance(&mut **tx, player_id).await?;
+    if cost > balance {
+        return Err(PurchaseError::InsufficientFunds);
+    }
+
+    Ok(())
+}
+
+async fn find_platform(
+    tx: &mut Transaction<'static, Postgres>,
+    player_id: Uuid,
+    id: Uuid,
+) -> Result<OilPlatformModel, PurchaseError> {
+    match sqlx::query_as!(
+        OilPlatformModel,
+        "SELECT * FROM oil_platforms WHERE id = $1 AND player_id = $2",
+        id,
+        player_id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    {
+        Ok(platform) => Ok(platform),
+        Err(SqlxError::RowNotFound) => Err(PurchaseError::NotFound),
+        Err(_) => Err(PurchaseError::OtherError),
+    }
+}
+
+async fn find_platform_type(
+    tx: &mut Transaction<'static, Postgres>,
+    name: &str,
+) -> Result<PlatformTypeModel, PurchaseError> {
+    match sqlx::query_as!(
+        PlatformTypeModel,
+        "SELECT * FROM platform_types WHERE name = $1",
+        name
+    )
+    .fetch_one(&mut **tx)
+    .await
+    {
+        Ok(platform_type) => Ok(platform_type),
+        Err(SqlxError::RowNotFound) => Err(PurchaseError::UnknownPlatformType),
+        Err(_) => Err(PurchaseError::OtherError),
+    }
+}
+
+async fn check_unlocked(
+    tx: &mut Transaction<'static, Postgres>,
+    player_id: Uuid,
+    platform_type: &PlatformTypeModel,
+) -> Result<(), PurchaseError> {
+    match locked_platform_types(&mut **tx, player_id, Some(&platform_type.name)).await {
+        Ok(locked) if locked.is_empty() => Ok(()),
+        Ok(_) => Err(PurchaseError::NotResearched),
+        Err(_) => Err(PurchaseError::OtherError),
+    }
+}
+
+// the platform type's maximum level raised by the player's research
+async fn max_level(
+    tx: &mut Transaction<'static, Postgres>,
+    player_id: Uuid,
+    platform_type: &PlatformTypeModel,
+) -> Result<i16, PurchaseError> {
+    match research_effects(&mut **tx, player_id).await {
+        Ok(effects) => Ok(effects.max_level(platform_type.max_level)),
+        Err(_) => Err(PurchaseError::OtherError),
+    }
+}
+
+// inserts the platform and debits its cost, the balance must be checked first
+async fn insert_platform(
+    tx: &mut Transaction<'static, Postgres>,
+    player_id: Uuid,
+    platform_type: &PlatformTypeModel,
+) -> Result<OilPlatformModel, PurchaseError> {
+    let created = match sqlx::query_as!(
+        OilPlatformModel,
+        "INSERT INTO oil_platforms (player_id, platform_type, profitability) VALUES ($1, $2, $3) RETURNING *",
+        player_id,
+        platform_type.name,
+        platform_type.profitability,
+    )
+    .fetch_one(&mut **tx)
+    .await
+    {
+        Ok(platform) => platform,
+        Err(_) => return Err(PurchaseError::OtherError),
+    };
+
+    let platform_create_tx = CreateMoneyTransactionModel {
+        item_id: Some(created.id),
+        amount: Money::from(platform_type.cost),
+        reduces_balance: true,
+        kind: TransactionKind::PlatformPurchase,
+        item_type: ItemType::Platform,
+    };
+    insert_transaction(&mut **tx, player_id, platform_create_tx).await?;
+
+    Ok(created)
+}
+
+fn upgrade_quote(
+    platform: &OilPlatformModel,
+    platform_type: &PlatformTypeModel,
+) -> Result<UpgradeQuoteModel, PurchaseError> {
+    game_config()
+        .upgrade_quote(
+            platform_type.upgrade_cost,
+            platform_type.profitability,
+            platform.platform_level,
+        )
+        .ok_or(PurchaseError::Overflow)
+}
+
+// raises the platform by one level and debits the quoted cost, the balance
+// and the maximum level must be checked first
+async fn apply_upgrade(
+    tx: &mut Transaction<'static, Postgres>,
+    player_id: Uuid,
+    platform: &OilPlatformModel,
+    quote: &UpgradeQuoteModel,
+) -> Result<OilPlatformModel, PurchaseError> {
+    let profitability = match platform.profitability.checked_add(quote.profitability_gain) {
+        Some(profitability) => profitability,
+        None => return Err(PurchaseError::Overflow),
+    };
+
+    let updated = match sqlx::query_as!(
+        OilPlatformModel,
+        "UPDATE oil_platforms SET platform_level = $1, profitability = $2, updated_at = $3 WHERE id = $4 RETURNING *",
+        platform.platform_level + 1,
+        profitability,
+        chrono::Utc::now().timestamp(),
+        platform.id
+    )
+    .fetch_one(&mut **tx)
+    .await
+    {
+        Ok(platform) => platform,
+        Err(_) => return Err(PurchaseError::OtherError),
+    };
+
+    let platform_update_tx = CreateMoneyTransactionModel {
+        item_id: Some(updated.id),
+        amount: quote.cost,
+        reduces_balance: true,
+        kind: TransactionKind::PlatformUpgrade,
+        item_type: ItemType::Platform,
+    };
+    insert_transaction(&mut **tx, player_id, platform_update_tx).await?;
+
+    Ok(updated)
+}
+
+impl From<MoneyTransactionError> for PurchaseError {
+    fn from(e: MoneyTransactionError) -> Self {
+        match e {
+            MoneyTransactionError::Overflow => PurchaseError::Overflow,
+            _ => PurchaseError::OtherError,
+        }
+    }
+}
+
+async fn commit(tx: Transaction<'static, Postgres>) -> Result<(), PurchaseError> {
+    tx.commit().await.map_err(|_| PurchaseError::OtherError)
+}
@@ -0,0 +1,119 @@
+use shared_db::{
+    model::{
+        CreateMoneyTransactionModel, ItemType, Money, TransactionFilterModel, TransactionKind,
+    },
+    repo::transactions_repo::TransactionsRepo,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+async fn book(
+    pool: &PgPool,
+    repo: &TransactionsRepo,
+    player_id: Uuid,
+    kind: TransactionKind,
+    amount: i64,
+    created_at: i64,
+) {
+    let reduces_balance = matches!(
+        kind,
+        TransactionKind::PlatformPurchase | TransactionKind::Upkeep
+    );
+    let entry = CreateMoneyTransactionModel {
+        item_id: None,
+        amount: Money::from(amount),
+        reduces_balance,
+        kind,
+        item_type: ItemType::System,
+    };
+    let created = repo.create(player_id, entry).await.unwrap();
+    sqlx::query!(
+        "UPDATE money_transactions SET created_at = $1 WHERE id = $2",
+        created_at,
+        created.id
+    )
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+// (kind, running balance) of every entry, oldest first
+async fn history(repo: &TransactionsRepo, player_id: Uuid) -> Vec<(TransactionKind, Money)> {
+    let filter = TransactionFilterModel {
+        page: 1,
+        limit: 100,
+        from: None,
+        to: None,
+        reduces_balance: None,
+        item_id: None,
+    };
+    let page = repo.get_history(player_id, filter).await.unwrap();
+    page.entries
+        .into_iter()
+        .rev()
+        .map(|entry| (entry.kind, entry.running_balance))
+        .collect()
+}
+
+// needs a database the role in `DATABASE_URL` may create test databases on,
+// run with `cargo test -- --ignored`
+#[sqlx::test(migrations = "../migrations")]
+#[ignore]
+async fn compaction_keeps_the_running_balances_of_the_history(pool: PgPool) {
+    let repo = TransactionsRepo::new(pool.clone());
+    let player_id: Uuid =
+        sqlx::query_scalar!("INSERT INTO players (username) VALUES ('bob') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    // all in one day, the purchase is booked in the same second as the
+    // earnings before it
+    let day = (chrono::Utc::now().timestamp() / DAY_SECS - 2) * DAY_SECS;
+    let entries = [
+        (TransactionKind::StartingBalance, 1000, 0),
+        (TransactionKind::Earnings, 300, 10),
+        (TransactionKind::Upkeep, 20, 10),
+        (TransactionKind::Earnings, 300, 20),
+        (TransactionKind::Upkeep, 20, 20),
+        (TransactionKind::PlatformPurchase, 1500, 20),
+        (TransactionKind::Earnings, 300, 30),
+        (TransactionKind::Earnings, 300, 40),
+    ];
+    for (kind, amount, offset) in entries {
+        book(&pool, &repo, player_id, kind, amount, day + offset).await;
+    }
+
+    let before = history(&repo, player_id).await;
+    let removed = repo.compact_earnings(day + DAY_SECS).await.unwrap();
+    let after = history(&repo, player_id).await;
+
+    assert_eq!(removed, 3);
+    assert_eq!(
+        after,
+        vec![
+            (TransactionKind::StartingBalance, Money::from(1000)),
+            (TransactionKind::Earnings, Money::from(1600)),
+            (TransactionKind::Upkeep, Money::from(1560)),
+            (TransactionKind::PlatformPurchase, Money::from(60)),
+            (TransactionKind::Earnings, Money::from(660)),
+        ]
+    );
+
+    // entries that were not compacted, and the last entry of every
+    // compacted run, show the same running balance as before
+    for (kind, balance) in [
+        (TransactionKind::StartingBalance, Money::from(1000)),
+        (TransactionKind::Upkeep, Money::from(1560)),
+        (TransactionKind::PlatformPurchase, Money::from(60)),
+        (TransactionKind::Earnings, Money::from(660)),
+    ] {
+        assert!(before.contains(&(kind, balance)));
+    }
+    assert_eq!(
+        repo.get_available_balance(player_id).await.unwrap(),
+        Money::from(660)
+    );
+}
@@ -0,0 +1,65 @@
+use shared_db::{
+    config::{game_config, load_game_config},
+    model::{CreateMoneyTransactionModel, ItemType, Money, TransactionKind},
+    repo::transactions_repo::TransactionsRepo,
+    service::purchase_service::PurchaseService,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+fn load_config() {
+    std::env::set_var(
+        "GAME_CONFIG_PATH",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../game_config.toml"),
+    );
+    load_game_config().unwrap();
+}
+
+// needs a database the role in `DATABASE_URL` may create test databases on,
+// run with `cargo test -- --ignored`
+#[sqlx::test(migrations = "../migrations")]
+#[ignore]
+async fn platforms_bought_while_away_do_not_earn_for_the_time_before(pool: PgPool) {
+    load_config();
+    let repo = TransactionsRepo::new(pool.clone());
+    let purchases = PurchaseService::new(pool.clone());
+    let player_id: Uuid =
+        sqlx::query_scalar!("INSERT INTO players (username) VALUES ('bob') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    let funds = CreateMoneyTransactionModel {
+        item_id: None,
+        amount: Money::from(10_000),
+        reduces_balance: false,
+        kind: TransactionKind::StartingBalance,
+        item_type: ItemType::System,
+    };
+    repo.create(player_id, funds).await.unwrap();
+    let owned = purchases.purchase_platform(player_id, "Rig").await.unwrap();
+
+    // the player is away for three periods and buys another platform
+    // through the API before coming back
+    let away_periods = 3;
+    sqlx::query!(
+        "UPDATE players SET last_settled_at = $1 WHERE id = $2",
+        chrono::Utc::now().timestamp() - away_periods * game_config().tick_period_secs,
+        player_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let bought = purchases.purchase_platform(player_id, "Rig").await.unwrap();
+    let settlement = repo.settle_offline(player_id).await.unwrap();
+
+    // the time away was settled by the purchase, with the platform owned
+    // back then only
+    assert_eq!(settlement.periods, 0);
+    let earners: Vec<Uuid> =
+        sqlx::query_scalar!("SELECT DISTINCT platform_id FROM platform_earnings")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(earners, vec![owned.id]);
+    assert_ne!(bought.id, owned.id);
+}
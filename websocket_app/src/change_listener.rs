@@ -0,0 +1,181 @@
+use log::*;
+use shared_db::{
+    constants::GAME_CHANGES_CHANNEL, model::ChangeEventModel, protocol::ServerMessage,
+    repo::generic::Repo,
+};
+use sqlx::postgres::{PgListener, PgPool};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
+
+use crate::AppState;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const ACHIEVEMENT_CHECK_PERIOD: Duration = Duration::from_secs(1);
+
+/// Listens for the entity change notifications sent by the database
+/// triggers and pushes them to the sockets of the affected player. Players
+/// with changes that can meet an achievement rule are checked for newly
+/// unlocked achievements.
+pub async fn run_change_listener(pool: PgPool, state: Arc<AppState>) {
+    let pending: Arc<Mutex<HashMap<Uuid, i64>>> = Arc::default();
+    tokio::spawn(run_achievement_checks(state.clone(), pending.clone()));
+
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to connect change listener: {}", e);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+        if let Err(e) = listener.listen(GAME_CHANGES_CHANNEL).await {
+            error!("Failed to listen for game changes: {}", e);
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        // the listener reconnects by itself, notifications sent while it was
+        // disconnected are lost
+        loop {
+            let notification = match listener.recv().await {
+                Ok(notification) => notification,
+                Err(e) => {
+                    error!("Failed to receive game change: {}", e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+            let event: ChangeEventModel = match serde_json::from_str(notification.payload()) {
+                Ok(event) => event,
+                Err(e) => {
+                    error!("Malformed game change {}: {}", notification.payload(), e);
+                    continue;
+                }
+            };
+
+            if let Some(message) = get_change_data(&state, &event).await {
+                state.hub.publish(event.player_id, message);
+            }
+
+            // the earliest change of a player since the last check times the
+            // next one, so a rule met in between is never judged too late
+            if affects_achievements(&event) {
+                pending
+                    .lock()
+                    .expect("achievement checks lock poisoned")
+                    .entry(event.player_id)
+                    .or_insert(event.at);
+            }
+        }
+    }
+}
+
+// platform updates are wear or upgrades, and upgrades are also booked in the
+// ledger; unlocks are pushed by their own notifications
+fn affects_achievements(event: &ChangeEventModel) -> bool {
+    matches!(
+        (event.table.as_str(), event.operation.as_str()),
+        ("money_transactions", _) | ("beer_purchases", _) | ("oil_platforms", "INSERT" | "DELETE")
+    )
+}
+
+// checks every player with changes since the previous check, so that a burst
+// of changes costs a single check per player and never holds up the listener
+async fn run_achievement_checks(state: Arc<AppState>, pending: Arc<Mutex<HashMap<Uuid, i64>>>) {
+    let mut interval = tokio::time::interval(ACHIEVEMENT_CHECK_PERIOD);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        let changes: Vec<(Uuid, i64)> = pending
+            .lock()
+            .expect("achievement checks lock poisoned")
+            .drain()
+            .collect();
+        for (player_id, at) in changes {
+            if let Err(e) = state.achievement_service.evaluate(player_id, at).await {
+                error!(
+                    "Failed to check achievements of player {}: {}",
+                    player_id, e
+                );
+            }
+        }
+    }
+}
+
+async fn get_change_data(state: &AppState, event: &ChangeEventModel) -> Option<ServerMessage> {
+    match (event.table.as_str(), event.operation.as_str()) {
+        ("oil_platforms", "DELETE") => Some(ServerMessage::PlatformRemoved {
+            platform_id: event.id,
+        }),
+        ("oil_platforms", _) => match state
+            .platform_repo
+            .get_by_id(event.player_id, event.id)
+            .await
+        {
+            Ok(platform) => Some(ServerMessage::PlatformChanged { platform }),
+            Err(e) => {
+                error!("Failed to load changed platform {}: {}", event.id, e);
+                None
+            }
+        },
+        ("beer_purchases", _) => {
+            let beer_id = event.beer_id?;
+            match state.beer_repo.get_by_id(event.player_id, beer_id).await {
+                Ok(beer) => Some(ServerMessage::BeerPurchased { beer }),
+                Err(e) => {
+                    error!("Failed to load purchased beer {}: {}", beer_id, e);
+                    None
+                }
+            }
+        }
+        ("player_achievements", _) => match state
+            .achievement_repo
+            .get_by_id(event.player_id, event.id)
+            .await
+        {
+            Ok(achievement) => Some(ServerMessage::AchievementUnlocked { achievement }),
+            Err(e) => {
+                error!("Failed to load unlocked achievement {}: {}", event.id, e);
+                None
+            }
+        },
+        ("money_transactions", _) => {
+            let transaction = match state
+                .finance_repo
+                .get_by_id(event.player_id, event.id)
+                .await
+            {
+                Ok(transaction) => transaction,
+                Err(e) => {
+                    error!("Failed to load transaction {}: {}", event.id, e);
+                    return None;
+                }
+            };
+            match state
+                .finance_repo
+                .get_available_balance(event.player_id)
+                .await
+            {
+                Ok(balance) => Some(ServerMessage::TransactionRecorded {
+                    transaction,
+                    balance,
+                }),
+                Err(e) => {
+                    error!(
+                        "Failed to load balance of player {}: {}",
+                        event.player_id, e
+                    );
+                    None
+                }
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,46 @@
+use log::*;
+use std::{sync::Arc, time::Duration};
+
+use crate::AppState;
+
+/// Periodically rolls old earnings entries into daily entries, so that the
+/// ledger does not grow by one row per tick forever, drops oil prices
+/// older than the longest offline progress that can still be settled and
+/// purges expired sessions and socket tickets.
+pub async fn run_compaction(
+    state: Arc<AppState>,
+    period: Duration,
+    retention_secs: i64,
+    max_offline_secs: i64,
+) {
+    let mut interval = tokio::time::interval(period);
+
+    loop {
+        interval.tick().await;
+
+        let now = chrono::Utc::now().timestamp();
+        let before = now - retention_secs;
+        match state.finance_repo.compact_earnings(before).await {
+            Ok(0) => (),
+            Ok(removed) => info!("Compacted {} earnings entries", removed),
+            Err(e) => error!("Failed to compact earnings: {}", e),
+        }
+
+        // offline progress is settled at the prices of the missed periods
+        match state
+            .oil_price_repo
+            .delete_before(now - max_offline_secs)
+            .await
+        {
+            Ok(0) => (),
+            Ok(removed) => info!("Dropped {} old oil prices", removed),
+            Err(e) => error!("Failed to drop old oil prices: {}", e),
+        }
+
+        match state.session_repo.purge_expired().await {
+            Ok(0) => (),
+            Ok(removed) => info!("Purged {} expired sessions and tickets", removed),
+            Err(e) => error!("Failed to purge expired sessions: {}", e),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use log::*;
+use shared_db::repo::{session_repo::SessionError, transactions_repo::MoneyTransactionError};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use std::{future::Future, time::Duration};
+
+const MAX_CONNECTIONS: u32 = 10;
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Creates the pool shared by all connections, waiting for the database to
+/// come up instead of giving up on the first failure.
+pub async fn connect_with_backoff(database_url: &str) -> PgPool {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        match PgPoolOptions::new()
+            .max_connections(MAX_CONNECTIONS)
+            .acquire_timeout(ACQUIRE_TIMEOUT)
+            .connect(database_url)
+            .await
+        {
+            Ok(pool) => {
+                info!("Connection to the database is successful!");
+                return pool;
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to connect to the database, retrying in {:?}: {:?}",
+                    backoff, err
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Errors that may go away when the operation is retried, like a lost
+/// connection, as opposed to ones that would fail the same way again.
+pub trait TransientError {
+    fn is_transient(&self) -> bool;
+}
+
+// pool and IO errors are all reported as `OtherError`
+impl TransientError for SessionError {
+    fn is_transient(&self) -> bool {
+        matches!(self, SessionError::OtherError)
+    }
+}
+
+impl TransientError for MoneyTransactionError {
+    fn is_transient(&self) -> bool {
+        matches!(self, MoneyTransactionError::OtherError)
+    }
+}
+
+/// Runs a database operation, retrying with exponential backoff while the
+/// database is unreachable. Returns the last error once all attempts fail,
+/// and any error that is not transient right away.
+pub async fn with_retry<T, E, F, Fut>(mut operation: F) -> Result<T, E>
+where
+    E: std::fmt::Display + TransientError,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Ok(res) => return Ok(res),
+            Err(e) if e.is_transient() && attempt < MAX_ATTEMPTS => {
+                warn!(
+                    "Database operation failed (attempt {}), retrying in {:?}: {}",
+                    attempt, backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use shared_db::protocol::ServerMessage;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Fans out messages produced for a player to all of that player's open
+/// sockets. A player's channel is dropped once the last socket closed.
+#[derive(Clone, Default)]
+pub struct BalanceHub {
+    channels: Arc<Mutex<HashMap<Uuid, broadcast::Sender<ServerMessage>>>>,
+}
+
+impl BalanceHub {
+    pub fn subscribe(&self, player_id: Uuid) -> broadcast::Receiver<ServerMessage> {
+        let mut channels = self.channels.lock().expect("hub lock poisoned");
+        channels
+            .entry(player_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Sends the message to the player's open sockets, if there are any.
+    pub fn publish(&self, player_id: Uuid, message: ServerMessage) {
+        let mut channels = self.channels.lock().expect("hub lock poisoned");
+        // sending only fails when the last socket of the player has closed
+        if let Some(sender) = channels.get(&player_id) {
+            if sender.send(message).is_err() {
+                channels.remove(&player_id);
+            }
+        }
+    }
+
+    /// Sends the message to every player with an open socket.
+    pub fn broadcast(&self, message: ServerMessage) {
+        let mut channels = self.channels.lock().expect("hub lock poisoned");
+        channels.retain(|_, sender| sender.send(message.clone()).is_ok());
+    }
+
+    /// Players with at least one open socket.
+    pub fn connected_players(&self) -> Vec<Uuid> {
+        let mut channels = self.channels.lock().expect("hub lock poisoned");
+        channels.retain(|_, sender| sender.receiver_count() > 0);
+        channels.keys().copied().collect()
+    }
+
+    pub fn is_connected(&self, player_id: Uuid) -> bool {
+        let channels = self.channels.lock().expect("hub lock poisoned");
+        channels
+            .get(&player_id)
+            .is_some_and(|sender| sender.receiver_count() > 0)
+    }
+}
@@ -1,18 +1,60 @@
+mod change_listener;
+mod compaction;
+mod db;
+mod hub;
 mod real_time_sender;
+mod tick_engine;
 
 use dotenv::dotenv;
 use futures_util::{SinkExt, StreamExt};
+use hub::BalanceHub;
 use log::*;
-use real_time_sender::get_realtime_financial_data;
-use std::{net::SocketAddr, time::Duration};
+use real_time_sender::{get_balance_data, get_error_data, get_offline_progress_data};
+use shared_db::config::load_game_config;
+use shared_db::protocol::{ClientMessage, ServerMessage};
+use shared_db::repo::{
+    achievement_repo::AchievementRepo,
+    beer_repo::BeerRepo,
+    generic::Repo,
+    oil_price_repo::OilPriceRepo,
+    platform_repo::OilPlaftormRepo,
+    session_repo::{SessionError, SessionRepo},
+    transactions_repo::TransactionsRepo,
+};
+use shared_db::service::{
+    achievement_service::AchievementService, event_service::MarketEventService,
+    oil_market_service::OilMarketService,
+};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tick_engine::TickEngine;
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
 use tokio_tungstenite::{
-    accept_async,
-    tungstenite::{Error, Message, Result},
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::StatusCode,
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Error, Message, Result,
+    },
 };
+use uuid::Uuid;
+
+/// Shared by the tick engine and all connection handlers, so that the whole
+/// server runs on a single connection pool.
+pub struct AppState {
+    pub session_repo: SessionRepo,
+    pub platform_repo: OilPlaftormRepo,
+    pub beer_repo: BeerRepo,
+    pub finance_repo: TransactionsRepo,
+    pub oil_price_repo: OilPriceRepo,
+    pub achievement_repo: AchievementRepo,
+    pub achievement_service: AchievementService,
+    pub hub: BalanceHub,
+}
 
-async fn accept_connection(peer: SocketAddr, stream: TcpStream) {
-    if let Err(e) = handle_connection(peer, stream).await {
+async fn accept_connection(peer: SocketAddr, stream: TcpStream, state: Arc<AppState>) {
+    if let Err(e) = handle_connection(peer, stream, state).await {
         match e {
             Error::ConnectionClosed | Error::Protocol(_) | Error::Utf8 => (),
             err => error!("Error processing connection: {}", err),
@@ -20,25 +62,87 @@ async fn accept_connection(peer: SocketAddr, stream: TcpStream) {
     }
 }
 
-async fn handle_connection(peer: SocketAddr, stream: TcpStream) -> Result<()> {
-    let ws_stream = accept_async(stream).await.expect("Failed to accept");
-    info!("New WebSocket connection: {}", peer);
+// clients connect to `/game-state?ticket=<socket ticket>`, tickets are
+// issued by the rocket app and can be used once
+fn parse_ticket(req: &Request) -> Option<String> {
+    req.uri()
+        .query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "ticket")
+        .map(|(_, value)| value.to_string())
+}
+
+async fn handle_connection(
+    peer: SocketAddr,
+    stream: TcpStream,
+    state: Arc<AppState>,
+) -> Result<()> {
+    let mut ticket = None;
+    // the error response type is dictated by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let callback = |req: &Request, response: Response| match parse_ticket(req) {
+        Some(value) => {
+            ticket = Some(value);
+            Ok(response)
+        }
+        None => {
+            let mut error_response = ErrorResponse::new(Some("Missing ticket".to_string()));
+            *error_response.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(error_response)
+        }
+    };
+    let ws_stream = accept_hdr_async(stream, callback).await?;
     let (mut ws_sender, mut ws_receiver) = ws_stream.split();
-    let mut interval = tokio::time::interval(Duration::from_millis(10000));
 
-    // Echo incoming WebSocket messages and send a message periodically every second.
+    // resolve the player the ticket was issued to
+    let ticket = ticket.unwrap_or_default();
+    let player = match db::with_retry(|| state.session_repo.redeem_socket_ticket(&ticket)).await {
+        Ok(player) => player,
+        Err(e) => {
+            // an unreachable database is not the client's fault, let it retry
+            let code = match e {
+                SessionError::OtherError => CloseCode::Again,
+                _ => CloseCode::Policy,
+            };
+            let close_frame = CloseFrame {
+                code,
+                reason: e.to_string().into(),
+            };
+            ws_sender.send(Message::Close(Some(close_frame))).await?;
+            return Ok(());
+        }
+    };
+    let player_id = player.id;
+    info!("New WebSocket connection: {} (player {})", peer, player_id);
+
+    // Answer client commands and forward the updates produced for this
+    // player by the tick engine.
+    let mut balance_updates = state.hub.subscribe(player_id);
 
-    let mut counter = 0;
+    // catch up on whatever was earned while the player was away, if that
+    // fails the next tick settles it instead
+    let hello = match get_offline_progress_data(&state.finance_repo, player_id).await {
+        Ok(message) => message,
+        Err(e) => {
+            error!(
+                "Failed to settle offline progress of player {}: {}",
+                player_id, e
+            );
+            get_error_data(e)
+        }
+    };
+    ws_sender.send(Message::Text(hello.to_frame())).await?;
 
     loop {
-        println!("Counter: {}", counter);
         tokio::select! {
             msg = ws_receiver.next() => {
                 match msg {
                     Some(msg) => {
                         let msg = msg?;
-                        if msg.is_text() ||msg.is_binary() {
-                            ws_sender.send(msg).await?;
+                        if msg.is_text() || msg.is_binary() {
+                            let reply = handle_command(&state, player_id, msg.to_text()?).await;
+                            ws_sender.send(Message::Text(reply.to_frame())).await?;
                         } else if msg.is_close() {
                             break;
                         }
@@ -46,10 +150,12 @@ async fn handle_connection(peer: SocketAddr, stream: TcpStream) -> Result<()> {
                     None => break,
                 }
             }
-            _ = interval.tick() => {
-                counter += 1;
-                let data = get_realtime_financial_data().await;
-                ws_sender.send(Message::Text(data)).await?;
+            update = balance_updates.recv() => {
+                match update {
+                    Ok(message) => ws_sender.send(Message::Text(message.to_frame())).await?,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
             }
         }
     }
@@ -57,11 +163,71 @@ async fn handle_connection(peer: SocketAddr, stream: TcpStream) -> Result<()> {
     Ok(())
 }
 
+async fn handle_command(state: &AppState, player_id: Uuid, frame: &str) -> ServerMessage {
+    let command = match ClientMessage::from_frame(frame) {
+        Ok(command) => command,
+        Err(e) => {
+            return ServerMessage::Error {
+                message: e.to_string(),
+            }
+        }
+    };
+
+    match command {
+        ClientMessage::Ping => ServerMessage::Pong,
+        ClientMessage::GetBalance => match get_balance_data(&state.finance_repo, player_id).await {
+            Ok(message) => message,
+            Err(e) => get_error_data(e),
+        },
+    }
+}
+
 #[tokio::main]
 async fn main() {
     dotenv().ok();
     env_logger::init();
 
+    let game_config = match load_game_config() {
+        Ok(config) => config,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = db::connect_with_backoff(&database_url).await;
+    let state = Arc::new(AppState {
+        session_repo: SessionRepo::new(pool.clone()),
+        platform_repo: OilPlaftormRepo::new(pool.clone()),
+        beer_repo: BeerRepo::new(pool.clone()),
+        finance_repo: TransactionsRepo::new(pool.clone()),
+        oil_price_repo: OilPriceRepo::new(pool.clone()),
+        achievement_repo: AchievementRepo::new(pool.clone()),
+        achievement_service: AchievementService::new(pool.clone()),
+        hub: BalanceHub::default(),
+    });
+
+    // earnings are credited by a single engine, sockets only subscribe
+    let engine = TickEngine::new(
+        state.clone(),
+        Duration::from_secs(game_config.tick_period_secs as u64),
+        MarketEventService::new(pool.clone()),
+        OilMarketService::new(pool.clone()),
+    );
+    tokio::spawn(engine.run());
+
+    tokio::spawn(compaction::run_compaction(
+        state.clone(),
+        Duration::from_secs(game_config.compaction_period_secs as u64),
+        game_config.earnings_retention_secs,
+        game_config.max_offline_secs,
+    ));
+
+    // entity changes made through the web app are pushed to open sockets,
+    // achievements are checked once a second for the players that changed
+    tokio::spawn(change_listener::run_change_listener(pool, state.clone()));
+
     let addr = "127.0.0.1:3030";
     let listener = TcpListener::bind(&addr).await.expect("Can't listen");
     info!("Listening on: {}", addr);
@@ -72,6 +238,6 @@ async fn main() {
             .expect("connected streams should have a peer address");
         info!("Peer address: {}", peer);
 
-        tokio::spawn(accept_connection(peer, stream));
+        tokio::spawn(accept_connection(peer, stream, state.clone()));
     }
 }
@@ -1,62 +1,62 @@
-use serde::{Deserialize, Serialize};
-use shared_db::{model::CreateMoneyTransactionModel, repo::transactions_repo::TransactionsRepo};
-use sqlx::postgres::PgPoolOptions;
+use shared_db::{
+    model::Money,
+    protocol::ServerMessage,
+    repo::transactions_repo::{MoneyTransactionError, TransactionsRepo},
+};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct BalanceData {
-    balance: i64,
-    just_earned: i64,
-}
+use crate::db::with_retry;
 
-pub async fn get_realtime_financial_data() -> String {
-    // separate database connection for streams
-    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let pool = match PgPoolOptions::new()
-        .max_connections(10)
-        .connect(&database_url)
-        .await
-    {
-        Ok(pool) => {
-            println!("Connection to the database is successful!");
-            pool
-        }
-        Err(err) => {
-            println!("Failed to connect to the database: {:?}", err);
-            std::process::exit(1);
-        }
-    };
-    let finance_repo = TransactionsRepo::new(pool);
-
-    // calculate earnings for this period
-    let recent_earnings = match finance_repo.get_period_platform_earnings().await {
-        Ok(result) => result,
-        Err(_) => 0,
-    };
-
-    // update balance
-    let earnings_tx = CreateMoneyTransactionModel {
-        item_id: Some(Uuid::nil()),
-        amount: recent_earnings,
-        reduces_balance: false,
-    };
-    let _ = match finance_repo.create(earnings_tx).await {
-        Ok(_) => true,
-        Err(_) => false,
-    };
+pub async fn get_realtime_financial_data(
+    finance_repo: &TransactionsRepo,
+    player_id: Uuid,
+) -> Result<ServerMessage, MoneyTransactionError> {
+    // credit earnings for the periods since the last settlement
+    let settlement = with_retry(|| finance_repo.settle(player_id)).await?;
 
     // retrieve updated balance
-    let current_balance = match finance_repo.get_available_balance().await {
-        Ok(result) => result,
-        Err(_) => 0,
-    };
+    let current_balance = with_retry(|| finance_repo.get_available_balance(player_id)).await?;
+
+    Ok(ServerMessage::BalanceUpdate {
+        balance: current_balance,
+        just_earned: settlement.earned,
+        just_paid_upkeep: settlement.upkeep,
+    })
+}
+
+/// Greets a freshly connected client, crediting everything earned while the
+/// player had no game open.
+pub async fn get_offline_progress_data(
+    finance_repo: &TransactionsRepo,
+    player_id: Uuid,
+) -> Result<ServerMessage, MoneyTransactionError> {
+    let settlement = with_retry(|| finance_repo.settle_offline(player_id)).await?;
+    let current_balance = with_retry(|| finance_repo.get_available_balance(player_id)).await?;
 
-    let response_data = BalanceData {
+    Ok(ServerMessage::Hello {
+        player_id,
         balance: current_balance,
-        just_earned: recent_earnings,
-    };
-    match serde_json::to_string(&response_data) {
-        Ok(res) => res,
-        Err(_) => "".to_string(),
+        earned_while_away: settlement.earned,
+    })
+}
+
+/// Current balance without settling, for clients asking between ticks.
+pub async fn get_balance_data(
+    finance_repo: &TransactionsRepo,
+    player_id: Uuid,
+) -> Result<ServerMessage, MoneyTransactionError> {
+    let current_balance = with_retry(|| finance_repo.get_available_balance(player_id)).await?;
+
+    Ok(ServerMessage::BalanceUpdate {
+        balance: current_balance,
+        just_earned: Money::ZERO,
+        just_paid_upkeep: Money::ZERO,
+    })
+}
+
+/// Tells the client that its game state could not be refreshed.
+pub fn get_error_data(e: MoneyTransactionError) -> ServerMessage {
+    ServerMessage::Error {
+        message: format!("Game state temporarily unavailable: {}", e),
     }
 }
@@ -0,0 +1,124 @@
+use log::*;
+use shared_db::{
+    protocol::ServerMessage,
+    service::{event_service::MarketEventService, oil_market_service::OilMarketService},
+};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
+
+use crate::{
+    real_time_sender::{get_error_data, get_realtime_financial_data},
+    AppState,
+};
+
+/// Credits platform earnings once per period for every player with an open
+/// socket, independent of how many sockets that are, rolls market events and
+/// moves the oil price. Players without a socket are settled as offline
+/// progress when they come back.
+pub struct TickEngine {
+    state: Arc<AppState>,
+    period: Duration,
+    events: MarketEventService,
+    market: OilMarketService,
+}
+
+impl TickEngine {
+    pub fn new(
+        state: Arc<AppState>,
+        period: Duration,
+        events: MarketEventService,
+        market: OilMarketService,
+    ) -> Self {
+        TickEngine {
+            state,
+            period,
+            events,
+            market,
+        }
+    }
+
+    /// Starts one ticker per connected player, picking up newly connected
+    /// players on every period.
+    pub async fn run(mut self) {
+        let running: Arc<Mutex<HashSet<Uuid>>> = Arc::default();
+        let mut interval = tokio::time::interval(self.period);
+
+        loop {
+            interval.tick().await;
+            let now = chrono::Utc::now().timestamp();
+
+            match self.market.step(now).await {
+                Ok(price) => self.state.hub.broadcast(ServerMessage::OilPrice { price }),
+                Err(e) => error!("Failed to move the oil price: {}", e),
+            }
+
+            match self.events.roll(now).await {
+                Ok(Some(event)) => {
+                    info!("Market event {} started", event.kind);
+                    self.state
+                        .hub
+                        .broadcast(ServerMessage::MarketEvent { event });
+                }
+                Ok(None) => (),
+                Err(e) => error!("Failed to roll market events: {}", e),
+            }
+
+            for player_id in self.state.hub.connected_players() {
+                if running
+                    .lock()
+                    .expect("ticker lock poisoned")
+                    .insert(player_id)
+                {
+                    info!("Starting earnings ticker for player {}", player_id);
+                    tokio::spawn(run_player_ticker(
+                        player_id,
+                        self.state.clone(),
+                        self.period,
+                        running.clone(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+// runs until the last socket of the player has closed
+async fn run_player_ticker(
+    player_id: Uuid,
+    state: Arc<AppState>,
+    period: Duration,
+    running: Arc<Mutex<HashSet<Uuid>>>,
+) {
+    let mut interval = tokio::time::interval(period);
+    // ticks missed while retrying are not replayed, settlement catches up
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // the first tick completes immediately, earnings are due after one period
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        if !state.hub.is_connected(player_id) {
+            info!("Stopping earnings ticker for player {}", player_id);
+            running
+                .lock()
+                .expect("ticker lock poisoned")
+                .remove(&player_id);
+            return;
+        }
+
+        // missed periods are caught up by the next successful settlement
+        let data = match get_realtime_financial_data(&state.finance_repo, player_id).await {
+            Ok(data) => data,
+            Err(e) => {
+                error!("Failed to settle earnings of player {}: {}", player_id, e);
+                get_error_data(e)
+            }
+        };
+        state.hub.publish(player_id, data);
+    }
+}